@@ -1,5 +1,8 @@
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
 
 lazy_static! {
     static ref DIGITS_PATTERN: Regex = Regex::new(r"(\d).*?(\d)?\D*$").unwrap();
@@ -30,55 +33,378 @@ pub fn step_1(lines: impl IntoIterator<Item = String>) {
     println!("sum: {}", sum);
 }
 
-const DIGIT_PATTERN_STRING: &str = r"\d|zero|one|two|three|four|five|six|seven|eight|nine";
+const ENGLISH_WORDS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const FRENCH_WORDS: [&str; 10] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+];
+const GERMAN_WORDS: [&str; 10] = [
+    "null", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+];
+const SPANISH_WORDS: [&str; 10] = [
+    "cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+];
+// there's no roman numeral for zero
+const ROMAN_NUMERALS: [&str; 9] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX"];
 
-lazy_static! {
-    static ref FIRST_DIGIT_PATTERN: Regex = Regex::new(DIGIT_PATTERN_STRING).unwrap();
-    static ref LAST_DIGIT_PATTERN: Regex =
-        Regex::new(&(r".*(?<digit>".to_owned() + &DIGIT_PATTERN_STRING + r")")).unwrap();
+pub struct Vocabulary {
+    values: HashMap<String, i32>,
+    first_digit_pattern: Regex,
+    last_digit_pattern: Regex,
 }
 
-fn parse_digit(text: &str) -> Option<i32> {
-    match text.parse::<i32>() {
-        Ok(digit) => return Some(digit),
-        Err(_) => {}
-    }
-
-    return match text {
-        "zero" => Some(0),
-        "one" => Some(1),
-        "two" => Some(2),
-        "three" => Some(3),
-        "four" => Some(4),
-        "five" => Some(5),
-        "six" => Some(6),
-        "seven" => Some(7),
-        "eight" => Some(8),
-        "nine" => Some(9),
-        _ => None,
-    };
-}
+impl Vocabulary {
+    fn new(words: Vec<(String, i32)>) -> Vocabulary {
+        // when several words start at the same place, like V and VIII, the longest one wins,
+        // since the regex takes the first alternative that matches
+        let mut sorted_words: Vec<&str> = words.iter().map(|(word, _)| word.as_str()).collect();
+        sorted_words.sort_by_key(|word| std::cmp::Reverse(word.len()));
+        let digit_pattern_string = std::iter::once(r"\d".to_owned())
+            .chain(sorted_words.into_iter().map(regex::escape))
+            .collect::<Vec<String>>()
+            .join("|");
 
-pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    let mut sum = 0;
-    for line in lines {
-        let first_digit_str = FIRST_DIGIT_PATTERN
-            .find(&line)
+        return Vocabulary {
+            first_digit_pattern: Regex::new(&digit_pattern_string).unwrap(),
+            // the greedy .* makes this find the match that starts last, so that overlapping
+            // words like "twone" and "eightwo" give the later word
+            last_digit_pattern: Regex::new(
+                &(r".*(?<digit>".to_owned() + &digit_pattern_string + r")"),
+            )
+            .unwrap(),
+            values: words.into_iter().collect(),
+        };
+    }
+
+    fn from_words(words: &[&str], first_value: i32) -> Vocabulary {
+        return Vocabulary::new(
+            words
+                .iter()
+                .enumerate()
+                .map(|(index, word)| (word.to_string(), first_value + index as i32))
+                .collect(),
+        );
+    }
+
+    pub fn english() -> Vocabulary {
+        return Vocabulary::from_words(&ENGLISH_WORDS, 0);
+    }
+
+    pub fn built_in(name: &str) -> Option<Vocabulary> {
+        return match name {
+            "english" => Some(Vocabulary::english()),
+            "french" => Some(Vocabulary::from_words(&FRENCH_WORDS, 0)),
+            "german" => Some(Vocabulary::from_words(&GERMAN_WORDS, 0)),
+            "spanish" => Some(Vocabulary::from_words(&SPANISH_WORDS, 0)),
+            "roman" => Some(Vocabulary::from_words(&ROMAN_NUMERALS, 1)),
+            _ => None,
+        };
+    }
+
+    pub fn from_file(path: &str) -> Vocabulary {
+        let contents = fs::read_to_string(path).expect("could not read vocabulary");
+        let mut words: Vec<(String, i32)> = Vec::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let split: Vec<&str> = line.split_whitespace().collect();
+            if split.len() != 2 {
+                panic!("expected a word and a digit: {}", line);
+            }
+            let value = match split[1].parse::<i32>() {
+                Ok(value) if (0..10).contains(&value) => value,
+                _ => panic!("not a digit: {}", split[1]),
+            };
+            words.push((split[0].to_owned(), value));
+        }
+        return Vocabulary::new(words);
+    }
+
+    fn parse_digit(&self, text: &str) -> Option<i32> {
+        match text.parse::<i32>() {
+            Ok(digit) => return Some(digit),
+            Err(_) => {}
+        }
+
+        return self.values.get(text).copied();
+    }
+
+    fn find_digits(&self, line: &str) -> (i32, i32) {
+        let first_digit_str = self
+            .first_digit_pattern
+            .find(line)
             .expect("could not get first digit")
             .as_str();
 
-        let last_digit_str = LAST_DIGIT_PATTERN
-            .captures(&line)
+        let last_digit_str = self
+            .last_digit_pattern
+            .captures(line)
             .expect("could not get last digit")
             .name("digit")
             .expect("could not get last digit")
             .as_str();
 
-        let first_digit = parse_digit(&first_digit_str).expect("could not parse first digit");
-        let last_digit = parse_digit(&last_digit_str).expect("could not parse last digit");
+        let first_digit = self
+            .parse_digit(first_digit_str)
+            .expect("could not parse first digit");
+        let last_digit = self
+            .parse_digit(last_digit_str)
+            .expect("could not parse last digit");
 
-        sum += first_digit * 10 + last_digit;
+        return (first_digit, last_digit);
     }
+}
 
-    println!("sum: {}", sum);
+pub fn solve_step_2_with_vocabulary(
+    lines: impl IntoIterator<Item = String>,
+    vocabulary: &Vocabulary,
+) -> u64 {
+    let mut sum = 0;
+    for line in lines {
+        let (first_digit, last_digit) = vocabulary.find_digits(&line);
+
+        sum += (first_digit * 10 + last_digit) as u64;
+    }
+
+    return sum;
+}
+
+pub fn step_2_with_vocabulary(lines: impl IntoIterator<Item = String>, vocabulary: &Vocabulary) {
+    println!("sum: {}", solve_step_2_with_vocabulary(lines, vocabulary));
+}
+
+pub fn step_2(lines: impl IntoIterator<Item = String>) {
+    step_2_with_vocabulary(lines, &Vocabulary::english());
+}
+
+// a trie over every token, with one 256 entry row per node so that each step is one lookup
+pub struct Scanner {
+    // 0 means there's no child, which works since the root is never a child
+    children: Vec<[u32; 256]>,
+    // the digit a token ending at each node stands for, or -1
+    values: Vec<i8>,
+}
+
+impl Scanner {
+    pub fn new(vocabulary: &Vocabulary) -> Scanner {
+        let mut scanner = Scanner {
+            children: vec![[0; 256]],
+            values: vec![-1],
+        };
+        for digit in 0..10 {
+            scanner.insert(&[b'0' + digit as u8], digit);
+        }
+        for (word, value) in &vocabulary.values {
+            scanner.insert(word.as_bytes(), *value);
+        }
+        return scanner;
+    }
+
+    pub fn digits_only() -> Scanner {
+        return Scanner::new(&Vocabulary::new(Vec::new()));
+    }
+
+    fn insert(&mut self, token: &[u8], value: i32) {
+        let mut node = 0;
+        for byte in token {
+            if self.children[node][*byte as usize] == 0 {
+                self.children.push([0; 256]);
+                self.values.push(-1);
+                self.children[node][*byte as usize] = (self.children.len() - 1) as u32;
+            }
+            node = self.children[node][*byte as usize] as usize;
+        }
+        self.values[node] = value as i8;
+    }
+
+    #[inline]
+    fn match_at(&self, line: &[u8], start: usize) -> Option<i32> {
+        let mut node = self.children[0][line[start] as usize] as usize;
+        if node == 0 {
+            // most bytes can't start a token, so bail out before setting anything up
+            return None;
+        }
+        let mut value = self.values[node];
+        for byte in &line[(start + 1)..] {
+            node = self.children[node][*byte as usize] as usize;
+            if node == 0 {
+                break;
+            }
+            if self.values[node] >= 0 {
+                value = self.values[node];
+            }
+        }
+        return if value >= 0 { Some(value as i32) } else { None };
+    }
+
+    // picks the same tokens as the regexes: the earliest and latest starting ones, and the longest
+    // of any that start at the same place
+    fn find_digits(&self, line: &[u8]) -> Option<(i32, i32)> {
+        let first_digit = (0..line.len()).find_map(|start| self.match_at(line, start))?;
+        let last_digit = (0..line.len())
+            .rev()
+            .find_map(|start| self.match_at(line, start))?;
+        return Some((first_digit, last_digit));
+    }
+
+    fn get_calibration_value(&self, line: &[u8]) -> u64 {
+        // read the same way BufRead::lines does, where a trailing \r is part of the newline
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let (first_digit, last_digit) = self.find_digits(line).expect("could not get first digit");
+        return (first_digit * 10 + last_digit) as u64;
+    }
+
+    // reads a block at a time, so the input never has to fit in memory
+    pub fn sum_calibration_values(&self, mut reader: impl Read) -> u64 {
+        let mut buffer: Vec<u8> = vec![0; STREAM_BUFFER_SIZE];
+        // how much of the start of the buffer is a line carried over from the last block
+        let mut carried = 0;
+        let mut sum = 0;
+        loop {
+            if carried == buffer.len() {
+                // a single line didn't fit, so make room for the rest of it
+                buffer.resize(buffer.len() * 2, 0);
+            }
+            let num_read = reader
+                .read(&mut buffer[carried..])
+                .expect("could not read input");
+            if num_read == 0 {
+                break;
+            }
+            let filled = carried + num_read;
+
+            let mut line_start = 0;
+            while let Some(offset) = find_newline(&buffer[line_start..filled]) {
+                sum += self.get_calibration_value(&buffer[line_start..(line_start + offset)]);
+                line_start += offset + 1;
+            }
+
+            buffer.copy_within(line_start..filled, 0);
+            carried = filled - line_start;
+        }
+
+        // the last line doesn't have to end in a newline
+        if carried > 0 {
+            sum += self.get_calibration_value(&buffer[..carried]);
+        }
+        return sum;
+    }
+}
+
+const STREAM_BUFFER_SIZE: usize = 1 << 20;
+
+const LOW_BITS: u64 = 0x0101010101010101;
+const HIGH_BITS: u64 = 0x8080808080808080;
+const NEWLINES: u64 = LOW_BITS * b'\n' as u64;
+
+// looks at eight bytes at a time
+fn find_newline(bytes: &[u8]) -> Option<usize> {
+    let mut chunks = bytes.chunks_exact(8);
+    let mut offset = 0;
+    for chunk in &mut chunks {
+        // bytes that were a newline become 0, and the subtraction sets the high bit of the
+        // lowest zero byte. higher bytes can be false positives, but never lower ones
+        let word = u64::from_le_bytes(chunk.try_into().unwrap()) ^ NEWLINES;
+        let zero_bytes = word.wrapping_sub(LOW_BITS) & !word & HIGH_BITS;
+        if zero_bytes != 0 {
+            return Some(offset + zero_bytes.trailing_zeros() as usize / 8);
+        }
+        offset += 8;
+    }
+    return chunks
+        .remainder()
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .map(|index| offset + index);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_words() {
+        // arrange
+        let vocabulary = Vocabulary::english();
+
+        // act
+        let result: Vec<(i32, i32)> = ["twone", "eightwo", "oneight3sevenine"]
+            .into_iter()
+            .map(|line| vocabulary.find_digits(line))
+            .collect();
+
+        // assert
+        assert_eq!(result, vec![(2, 1), (8, 2), (1, 9)]);
+    }
+
+    #[test]
+    fn test_roman_numerals_prefer_longest() {
+        // arrange
+        let vocabulary = Vocabulary::built_in("roman").unwrap();
+
+        // act
+        let result: Vec<(i32, i32)> = ["xVIIIx", "aIVb", "IX2"]
+            .into_iter()
+            .map(|line| vocabulary.find_digits(line))
+            .collect();
+
+        // assert
+        assert_eq!(result, vec![(8, 1), (4, 5), (9, 2)]);
+    }
+
+    #[test]
+    fn test_other_languages() {
+        // arrange
+        let lines = [
+            ("french", "abcdeuxtroisneufz"),
+            ("german", "fünfacht"),
+            ("spanish", "xsietecero4"),
+        ];
+
+        // act
+        let result: Vec<(i32, i32)> = lines
+            .into_iter()
+            .map(|(name, line)| Vocabulary::built_in(name).unwrap().find_digits(line))
+            .collect();
+
+        // assert
+        assert_eq!(result, vec![(2, 9), (5, 8), (7, 4)]);
+    }
+
+    #[test]
+    fn test_scanner_matches_regexes() {
+        // arrange
+        let lines = crate::generate::generate(1, 7, 500);
+        let vocabulary = Vocabulary::english();
+        let scanner = Scanner::new(&vocabulary);
+
+        for line in lines {
+            // act
+            let result = scanner.find_digits(line.as_bytes());
+
+            // assert
+            assert_eq!(result, Some(vocabulary.find_digits(&line)), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_scanner_streams_examples() {
+        // arrange
+        let step_1_input = crate::examples::example(1, 1).unwrap();
+        let step_2_input = crate::examples::example(1, 2)
+            .unwrap()
+            .replace('\n', "\r\n");
+
+        // act
+        let result = (
+            Scanner::digits_only().sum_calibration_values(step_1_input.as_bytes()),
+            Scanner::new(&Vocabulary::english()).sum_calibration_values(step_2_input.as_bytes()),
+        );
+
+        // assert
+        assert_eq!(result, (142, 281));
+    }
 }
@@ -1,3 +1,4 @@
+use crate::render::{Canvas, INSIDE, WALL};
 use std::vec::Vec;
 
 const NORTH: u8 = 1;
@@ -37,7 +38,7 @@ fn opposite_direction(direction: u8) -> u8 {
     };
 }
 
-fn get_pipe_directions(pipe_map: &Vec<String>, position: (usize, usize)) -> u8 {
+fn get_pipe_directions(pipe_map: &[String], position: (usize, usize)) -> u8 {
     return match pipe_map[position.0].as_bytes()[position.1] {
         b'-' => EAST | WEST,
         b'|' => NORTH | SOUTH,
@@ -55,9 +56,9 @@ struct State {
 }
 
 impl State {
-    fn update(&mut self, pipe_map: &Vec<String>) {
+    fn update(&mut self, pipe_map: &[String]) {
         self.position = move_position(self.position, self.direction).unwrap();
-        let directions = get_pipe_directions(&pipe_map, self.position);
+        let directions = get_pipe_directions(pipe_map, self.position);
         let opposite_current_direction = opposite_direction(self.direction);
         self.direction = directions ^ opposite_current_direction;
     }
@@ -105,21 +106,25 @@ pub fn step_1(lines: impl IntoIterator<Item = String>) {
     println!("furthest distance: {}", count);
 }
 
-pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    let pipe_map: Vec<String> = lines.into_iter().collect();
-    let (start_line_index, start_line) = (&pipe_map)
-        .into_iter()
+fn find_start(pipe_map: &[String]) -> (usize, usize) {
+    let (start_line_index, start_line) = pipe_map
+        .iter()
         .enumerate()
         .find(|(_, line)| line.contains("S"))
         .unwrap();
 
-    let start_position = (start_line_index, start_line.find("S").unwrap());
+    return (start_line_index, start_line.find("S").unwrap());
+}
+
+// tiles off the main loop are 0, and the start gets the directions of the pipe it stands for
+fn build_edge_map(pipe_map: &[String]) -> Vec<Vec<u8>> {
+    let start_position = find_start(pipe_map);
 
     let start_directions: Vec<u8> = [NORTH, SOUTH, EAST, WEST]
         .into_iter()
         .filter(|direction| {
             if let Some(moved_position) = move_position(start_position, *direction) {
-                return get_pipe_directions(&pipe_map, moved_position)
+                return get_pipe_directions(pipe_map, moved_position)
                     & opposite_direction(*direction)
                     != 0;
             }
@@ -143,43 +148,292 @@ pub fn step_2(lines: impl IntoIterator<Item = String>) {
     edge_map[start_position.0][start_position.1] = start_directions[0] | start_directions[1];
 
     loop {
-        state.update(&pipe_map);
+        state.update(pipe_map);
         if state.position == start_position {
             break;
         }
         edge_map[state.position.0][state.position.1] =
-            get_pipe_directions(&pipe_map, state.position);
+            get_pipe_directions(pipe_map, state.position);
     }
 
-    let area_inside_loop = edge_map
-        .into_iter()
+    return edge_map;
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Tile {
+    Outside,
+    Enclosed,
+    Pipe,
+    // a corner that closes an F--J or L--7 run, which doesn't count as crossing the loop again
+    SkippedCorner,
+}
+
+fn scan_row(edges: &[u8]) -> Vec<Tile> {
+    let mut tiles: Vec<Tile> = Vec::new();
+    let mut inside_loop = false;
+    let mut last_boundary = 0;
+    for &pipe in edges {
+        if pipe == 0 {
+            tiles.push(if inside_loop {
+                Tile::Enclosed
+            } else {
+                Tile::Outside
+            });
+            continue;
+        }
+        if pipe & (NORTH | SOUTH) == 0 {
+            tiles.push(Tile::Pipe);
+            continue;
+        }
+        if pipe != NORTH | SOUTH
+            && last_boundary & (NORTH | SOUTH) == opposite_direction(pipe & (NORTH | SOUTH))
+        {
+            // handle the F--J case, where even though the J has a vertical component,
+            // we don't enter or exit the loop.
+            tiles.push(Tile::SkippedCorner);
+            continue;
+        }
+        tiles.push(Tile::Pipe);
+        inside_loop = !inside_loop;
+        last_boundary = pipe;
+    }
+    return tiles;
+}
+
+fn count_enclosed(pipe_map: &[String]) -> usize {
+    return build_edge_map(pipe_map)
+        .iter()
         .map(|edges| {
-            let mut count = 0;
-            let mut inside_loop = false;
-            let mut last_boundary = 0;
-            for pipe in edges {
-                if pipe == 0 {
-                    if inside_loop {
-                        count += 1;
+            scan_row(edges)
+                .into_iter()
+                .filter(|tile| *tile == Tile::Enclosed)
+                .count()
+        })
+        .fold(0, |a, b| a + b);
+}
+
+pub fn step_2(lines: impl IntoIterator<Item = String>) {
+    let pipe_map: Vec<String> = lines.into_iter().collect();
+
+    println!("area inside loop: {}", count_enclosed(&pipe_map));
+}
+
+pub fn render(lines: Vec<String>) -> Canvas {
+    let edge_map = build_edge_map(&lines);
+    let width = edge_map.iter().map(|edges| edges.len()).max().unwrap_or(0);
+    let mut canvas = Canvas::new(width * 3, edge_map.len() * 3);
+
+    for (line_index, edges) in edge_map.iter().enumerate() {
+        let tiles = scan_row(edges);
+        for (byte_index, &pipe) in edges.iter().enumerate() {
+            let top = line_index * 3;
+            let left = byte_index * 3;
+            if tiles[byte_index] == Tile::Enclosed {
+                for row in top..(top + 3) {
+                    for column in left..(left + 3) {
+                        canvas.set(row, column, INSIDE);
                     }
-                    continue;
-                }
-                if pipe & (NORTH | SOUTH) == 0 {
-                    continue;
                 }
-                if pipe != NORTH | SOUTH
-                    && last_boundary & (NORTH | SOUTH) == opposite_direction(pipe & (NORTH | SOUTH))
-                {
-                    // handle the F--J case, where even though the J has a vertical component,
-                    // we don't enter or exit the loop.
-                    continue;
+            }
+            if pipe == 0 {
+                continue;
+            }
+
+            canvas.set(top + 1, left + 1, WALL);
+            for (direction, row, column) in [
+                (NORTH, top, left + 1),
+                (SOUTH, top + 2, left + 1),
+                (EAST, top + 1, left + 2),
+                (WEST, top + 1, left),
+            ] {
+                if pipe & direction != 0 {
+                    canvas.set(row, column, WALL);
                 }
-                inside_loop = !inside_loop;
-                last_boundary = pipe;
             }
-            return count;
-        })
-        .fold(0, |a, b| a + b);
+        }
+    }
+
+    return canvas;
+}
+
+fn get_box_drawing_glyph(pipe: u8) -> char {
+    return match pipe {
+        x if x == EAST | WEST => '─',
+        x if x == NORTH | SOUTH => '│',
+        x if x == NORTH | EAST => '└',
+        x if x == NORTH | WEST => '┘',
+        x if x == SOUTH | EAST => '┌',
+        x if x == SOUTH | WEST => '┐',
+        _ => panic!("unknown pipe"),
+    };
+}
+
+const ANSI_DIM: &str = "\x1b[2m";
+const ANSI_ENCLOSED: &str = "\x1b[32m";
+const ANSI_SKIPPED_CORNER: &str = "\x1b[1;33m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+pub fn pretty_print(lines: impl IntoIterator<Item = String>) {
+    let pipe_map: Vec<String> = lines.into_iter().collect();
+    let (drawn_lines, num_enclosed, num_skipped_corners) = draw_loop(&pipe_map);
+    for drawn_line in drawn_lines {
+        println!("{}", drawn_line);
+    }
+
+    println!();
+    println!(
+        "{} enclosed tiles, {} corners skipped by the F--J case",
+        num_enclosed, num_skipped_corners
+    );
+}
+
+// returns the drawn lines along with how many tiles are enclosed and how many corners were skipped
+fn draw_loop(pipe_map: &[String]) -> (Vec<String>, usize, usize) {
+    let edge_map = build_edge_map(pipe_map);
+
+    let mut drawn_lines: Vec<String> = Vec::new();
+    let mut num_enclosed = 0;
+    let mut num_skipped_corners = 0;
+    for (line_index, edges) in edge_map.iter().enumerate() {
+        let mut drawn_line = String::new();
+        for (byte_index, tile) in scan_row(edges).into_iter().enumerate() {
+            let pipe = edges[byte_index];
+            // pipes that aren't on the loop are still enclosed or outside, but are drawn so
+            // it's clear what they were
+            let junk_pipe = get_pipe_directions(pipe_map, (line_index, byte_index));
+            drawn_line += &match tile {
+                Tile::Pipe => get_box_drawing_glyph(pipe).to_string(),
+                Tile::SkippedCorner => {
+                    num_skipped_corners += 1;
+                    format!(
+                        "{}{}{}",
+                        ANSI_SKIPPED_CORNER,
+                        get_box_drawing_glyph(pipe),
+                        ANSI_RESET
+                    )
+                }
+                Tile::Enclosed => {
+                    num_enclosed += 1;
+                    format!("{}I{}", ANSI_ENCLOSED, ANSI_RESET)
+                }
+                Tile::Outside if junk_pipe != 0 => format!(
+                    "{}{}{}",
+                    ANSI_DIM,
+                    get_box_drawing_glyph(junk_pipe),
+                    ANSI_RESET
+                ),
+                Tile::Outside => format!("{}·{}", ANSI_DIM, ANSI_RESET),
+            };
+        }
+        drawn_lines.push(drawn_line);
+    }
+
+    return (drawn_lines, num_enclosed, num_skipped_corners);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the other step 2 examples from the puzzle, with how many tiles each encloses
+    const SQUEEZED_EXAMPLE: &str = "..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........";
+
+    const LARGER_EXAMPLE: &str = ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
+
+    const JUNK_EXAMPLE: &str = "FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L";
 
-    println!("area inside loop: {}", area_inside_loop);
+    fn to_lines(text: &str) -> Vec<String> {
+        return text.lines().map(|line| line.to_owned()).collect();
+    }
+
+    #[test]
+    fn test_enclosed_counts_for_step_2_examples() {
+        // arrange
+        let examples = [
+            (crate::examples::example_lines(10, 2).unwrap(), 4),
+            (to_lines(SQUEEZED_EXAMPLE), 4),
+            (to_lines(LARGER_EXAMPLE), 8),
+            (to_lines(JUNK_EXAMPLE), 10),
+        ];
+
+        for (pipe_map, expected) in examples {
+            // act
+            let result = count_enclosed(&pipe_map);
+
+            // assert
+            assert_eq!(result, expected);
+        }
+    }
+
+    #[test]
+    fn test_scan_row_crossings() {
+        // arrange
+        // F--J and L--7 each cross the loop once, while F--7 and L--J don't cross it at all
+        let pipe_map = vec!["F-J.L-7.|F7.LJ.|.".to_owned()];
+        let expected = "...I.......I..I..";
+        let edges: Vec<u8> = (0..pipe_map[0].len())
+            .map(|index| get_pipe_directions(&pipe_map, (0, index)))
+            .collect();
+
+        // act
+        let result = scan_row(&edges);
+
+        // assert
+        let enclosed: String = result
+            .iter()
+            .map(|tile| if *tile == Tile::Enclosed { 'I' } else { '.' })
+            .collect();
+        assert_eq!(enclosed, expected);
+    }
+
+    #[test]
+    fn test_draw_loop() {
+        // arrange
+        let pipe_map = to_lines("-....\n.S-7.\n.|.|.\n.L-J.\n.....");
+
+        // act
+        let (drawn_lines, num_enclosed, num_skipped_corners) = draw_loop(&pipe_map);
+
+        // assert
+        let outside = format!("{}·{}", ANSI_DIM, ANSI_RESET);
+        let junk = format!("{}─{}", ANSI_DIM, ANSI_RESET);
+        let enclosed = format!("{}I{}", ANSI_ENCLOSED, ANSI_RESET);
+        assert_eq!(
+            drawn_lines,
+            vec![
+                junk + &outside.repeat(4),
+                outside.clone() + "┌─┐" + &outside,
+                outside.clone() + "│" + &enclosed + "│" + &outside,
+                outside.clone() + "└─┘" + &outside,
+                outside.repeat(5),
+            ]
+        );
+        assert_eq!((num_enclosed, num_skipped_corners), (1, 0));
+    }
 }
@@ -1,78 +1,156 @@
+use crate::render::{Canvas, ACCENT, FAINT};
 use std::collections::HashSet;
 use std::vec::Vec;
 
-fn execute(lines: impl IntoIterator<Item = String>, empty_line_bonus: usize) {
-    let mut non_empty_rows: HashSet<usize> = HashSet::new();
-    let mut non_empty_columns: HashSet<usize> = HashSet::new();
+pub struct Image {
+    coordinates: Vec<(usize, usize)>,
+    empty_rows: Vec<usize>,
+    empty_columns: Vec<usize>,
+    num_rows: usize,
+    num_columns: usize,
+}
 
-    let mut coordinates: Vec<(usize, usize)> = Vec::new();
+impl Image {
+    pub fn parse(lines: impl IntoIterator<Item = String>) -> Image {
+        let mut non_empty_rows: HashSet<usize> = HashSet::new();
+        let mut non_empty_columns: HashSet<usize> = HashSet::new();
 
-    let mut num_rows = 0;
-    let mut num_columns = 0;
+        let mut coordinates: Vec<(usize, usize)> = Vec::new();
 
-    for (line_index, line) in lines.into_iter().enumerate() {
-        num_rows += 1;
-        if num_columns == 0 {
-            num_columns = line.len();
-        }
-        for (byte_index, byte) in line.bytes().enumerate() {
-            if byte == b'#' {
-                non_empty_rows.insert(line_index);
-                non_empty_columns.insert(byte_index);
-                coordinates.push((line_index, byte_index));
+        let mut num_rows = 0;
+        let mut num_columns = 0;
+
+        for (line_index, line) in lines.into_iter().enumerate() {
+            num_rows += 1;
+            if num_columns == 0 {
+                num_columns = line.len();
+            }
+            for (byte_index, byte) in line.bytes().enumerate() {
+                if byte == b'#' {
+                    non_empty_rows.insert(line_index);
+                    non_empty_columns.insert(byte_index);
+                    coordinates.push((line_index, byte_index));
+                }
             }
         }
+
+        let empty_rows: Vec<usize> = (0..num_rows)
+            .filter(|row_index| !non_empty_rows.contains(&row_index))
+            .collect();
+        let empty_columns: Vec<usize> = (0..num_columns)
+            .filter(|column_index| !non_empty_columns.contains(&column_index))
+            .collect();
+
+        return Image {
+            coordinates,
+            empty_rows,
+            empty_columns,
+            num_rows,
+            num_columns,
+        };
     }
 
-    let empty_rows: Vec<usize> = (0..num_rows)
-        .filter(|row_index| !non_empty_rows.contains(&row_index))
-        .collect();
-    let empty_columns: Vec<usize> = (0..num_columns)
-        .filter(|column_index| !non_empty_columns.contains(&column_index))
-        .collect();
-
-    let adjusted_coordinates: Vec<(usize, usize)> = coordinates
-        .into_iter()
-        .map(|(row, column)| {
-            let mut result = (row, column);
-
-            for row_index in &empty_rows {
-                if *row_index > row {
-                    break;
+    pub fn sum_of_distances(&self, empty_line_bonus: usize) -> usize {
+        let adjusted_coordinates: Vec<(usize, usize)> = self
+            .coordinates
+            .iter()
+            .map(|&(row, column)| {
+                let mut result = (row, column);
+
+                for row_index in &self.empty_rows {
+                    if *row_index > row {
+                        break;
+                    }
+                    result.0 += empty_line_bonus;
                 }
-                result.0 += empty_line_bonus;
-            }
 
-            for column_index in &empty_columns {
-                if *column_index > column {
-                    break;
+                for column_index in &self.empty_columns {
+                    if *column_index > column {
+                        break;
+                    }
+                    result.1 += empty_line_bonus;
                 }
-                result.1 += empty_line_bonus;
-            }
 
-            return result;
-        })
-        .collect();
-
-    let sum_of_distances = adjusted_coordinates
-        .iter()
-        .enumerate()
-        .map(|(index, a)| {
-            adjusted_coordinates
-                .iter()
-                .skip(index + 1)
-                .map(|b| a.0.abs_diff(b.0) + a.1.abs_diff(b.1))
-                .fold(0, |m, n| m + n)
-        })
-        .fold(0, |m, n| m + n);
-
-    println!("sum of distances: {}", sum_of_distances);
+                return result;
+            })
+            .collect();
+
+        return adjusted_coordinates
+            .iter()
+            .enumerate()
+            .map(|(index, a)| {
+                adjusted_coordinates
+                    .iter()
+                    .skip(index + 1)
+                    .map(|b| a.0.abs_diff(b.0) + a.1.abs_diff(b.1))
+                    .fold(0, |m, n| m + n)
+            })
+            .fold(0, |m, n| m + n);
+    }
+}
+
+pub fn solve_step_1(lines: impl IntoIterator<Item = String>) -> usize {
+    return Image::parse(lines).sum_of_distances(1);
 }
 
 pub fn step_1(lines: impl IntoIterator<Item = String>) {
-    execute(lines, 1);
+    println!("sum of distances: {}", solve_step_1(lines));
+}
+
+pub fn solve_step_2(lines: impl IntoIterator<Item = String>) -> usize {
+    return Image::parse(lines).sum_of_distances(999999);
 }
 
 pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    execute(lines, 999999);
+    println!("sum of distances: {}", solve_step_2(lines));
+}
+
+// a million times wider would leave nothing else visible
+const MAX_DRAWN_EMPTY_LINE_BONUS: usize = 3;
+
+fn get_drawn_offsets(num_lines: usize, empty_lines: &[usize], bonus: usize) -> Vec<usize> {
+    let mut offsets: Vec<usize> = Vec::new();
+    let mut offset = 0;
+    for line_index in 0..=num_lines {
+        offsets.push(offset);
+        offset += if empty_lines.contains(&line_index) {
+            1 + bonus
+        } else {
+            1
+        };
+    }
+    return offsets;
+}
+
+pub fn render(lines: Vec<String>, step: u32) -> Canvas {
+    let image = Image::parse(lines);
+    let empty_line_bonus = if step == 1 { 1 } else { 999999 };
+    let drawn_bonus = std::cmp::min(empty_line_bonus, MAX_DRAWN_EMPTY_LINE_BONUS);
+
+    let row_offsets = get_drawn_offsets(image.num_rows, &image.empty_rows, drawn_bonus);
+    let column_offsets = get_drawn_offsets(image.num_columns, &image.empty_columns, drawn_bonus);
+    let mut canvas = Canvas::new(
+        column_offsets[image.num_columns],
+        row_offsets[image.num_rows],
+    );
+
+    for row_index in &image.empty_rows {
+        for row in row_offsets[*row_index]..row_offsets[row_index + 1] {
+            for column in 0..column_offsets[image.num_columns] {
+                canvas.set(row, column, FAINT);
+            }
+        }
+    }
+    for column_index in &image.empty_columns {
+        for column in column_offsets[*column_index]..column_offsets[column_index + 1] {
+            for row in 0..row_offsets[image.num_rows] {
+                canvas.set(row, column, FAINT);
+            }
+        }
+    }
+    for (row, column) in &image.coordinates {
+        canvas.set(row_offsets[*row], column_offsets[*column], ACCENT);
+    }
+
+    return canvas;
 }
@@ -52,50 +52,232 @@ fn count_possible_combinations(
     return total;
 }
 
-pub fn step_1(lines: impl IntoIterator<Item = String>) {
+fn parse_line(line: &str, unfold: bool) -> (Vec<u8>, Vec<usize>) {
+    let split: Vec<&str> = line.split(" ").collect();
+    let record = split[0];
+    let groups: Vec<usize> = split[1]
+        .split(",")
+        .map(|group_str| group_str.parse::<usize>().unwrap())
+        .collect();
+
+    if !unfold {
+        return (record.as_bytes().to_vec(), groups);
+    }
+
+    let mut joined_record = record.to_owned();
+    let mut joined_groups = groups.clone();
+    for _ in 0..4 {
+        joined_record += "?";
+        joined_record += record;
+        joined_groups = joined_groups
+            .iter()
+            .chain(&groups)
+            .map(|ptr| *ptr)
+            .collect();
+    }
+    return (joined_record.into_bytes(), joined_groups);
+}
+
+pub fn count_for_line(line: &str, unfold: bool) -> u64 {
+    let (record, groups) = parse_line(line, unfold);
+    return count_possible_combinations(&record, &groups, &mut HashMap::new());
+}
+
+// branches with no arrangements are skipped using the counts, so getting the next arrangement
+// never takes more than a pass over the record
+pub struct Arrangements {
+    record: Vec<u8>,
+    groups: Vec<usize>,
+    memo: HashMap<String, u64>,
+    // (index into the record, index into the groups, the arrangement so far)
+    stack: Vec<(usize, usize, Vec<u8>)>,
+}
+
+impl Arrangements {
+    fn new(record: Vec<u8>, groups: Vec<usize>) -> Arrangements {
+        let mut arrangements = Arrangements {
+            record,
+            groups,
+            memo: HashMap::new(),
+            stack: Vec::new(),
+        };
+        arrangements.push_if_possible(0, 0, Vec::new());
+        return arrangements;
+    }
+
+    fn push_if_possible(&mut self, record_index: usize, group_index: usize, prefix: Vec<u8>) {
+        let record_index = std::cmp::min(record_index, self.record.len());
+        if count_possible_combinations(
+            &self.record[record_index..],
+            &self.groups[group_index..],
+            &mut self.memo,
+        ) > 0
+        {
+            self.stack.push((record_index, group_index, prefix));
+        }
+    }
+}
+
+impl Iterator for Arrangements {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while let Some((record_index, group_index, mut prefix)) = self.stack.pop() {
+            if group_index == self.groups.len() {
+                // every group is placed and the rest is known to have no #, so it's all .
+                prefix.resize(self.record.len(), b'.');
+                return Some(prefix.into_iter().map(|byte| byte as char).collect());
+            }
+
+            let record = &self.record[record_index..];
+            let group = self.groups[group_index];
+            let byte = record[0];
+            // pushed first so that it's explored after the . branch, like in the counting
+            if byte != b'.' && record.len() >= group && !record[1..group].contains(&b'.') {
+                let mut group_prefix = prefix.clone();
+                group_prefix.resize(group_prefix.len() + group, b'#');
+                if record.len() > group && record[group] != b'#' {
+                    group_prefix.push(b'.');
+                    self.push_if_possible(record_index + group + 1, group_index + 1, group_prefix);
+                } else if record.len() == group && group_index + 1 == self.groups.len() {
+                    self.stack
+                        .push((record_index + group, group_index + 1, group_prefix));
+                }
+            }
+            if byte != b'#' {
+                prefix.push(b'.');
+                self.push_if_possible(record_index + 1, group_index, prefix);
+            }
+        }
+
+        return None;
+    }
+}
+
+pub fn get_arrangements(line: &str, unfold: bool) -> Arrangements {
+    let (record, groups) = parse_line(line, unfold);
+    return Arrangements::new(record, groups);
+}
+
+pub fn get_probabilities(line: &str, unfold: bool) -> Vec<(usize, f64)> {
+    let (record, groups) = parse_line(line, unfold);
+    let total = count_possible_combinations(&record, &groups, &mut HashMap::new());
+
+    let mut probabilities: Vec<(usize, f64)> = Vec::new();
+    for (byte_index, byte) in record.iter().enumerate() {
+        if *byte != b'?' {
+            continue;
+        }
+        let mut damaged_record = record.clone();
+        damaged_record[byte_index] = b'#';
+        let num_damaged =
+            count_possible_combinations(&damaged_record, &groups, &mut HashMap::new());
+        probabilities.push((byte_index, num_damaged as f64 / total as f64));
+    }
+    return probabilities;
+}
+
+pub fn print_line_report(line: &str, unfold: bool, limit: usize) {
+    let (record, _) = parse_line(line, unfold);
+    let total = count_for_line(line, unfold);
+    println!(
+        "{}",
+        record.iter().map(|byte| *byte as char).collect::<String>()
+    );
+    println!("arrangements: {}", total);
+    for arrangement in get_arrangements(line, unfold).take(limit) {
+        println!("  {}", arrangement);
+    }
+    if total > limit as u64 {
+        println!("  ... and {} more", total - limit as u64);
+    }
+    if total == 0 {
+        return;
+    }
+    println!("chance of each ? being #:");
+    for (byte_index, probability) in get_probabilities(line, unfold) {
+        println!("  byte {:>3}: {:.3}", byte_index, probability);
+    }
+}
+
+pub fn print_arrangements(lines: impl IntoIterator<Item = String>, step: u32, limit: usize) {
+    for line in lines {
+        print_line_report(&line, step == 2, limit);
+        println!();
+    }
+}
+
+pub fn solve_step_1(lines: impl IntoIterator<Item = String>) -> u64 {
     let mut total = 0;
 
     for line in lines {
-        let split: Vec<&str> = line.split(" ").collect();
-        let record = split[0];
-        let groups: Vec<usize> = split[1]
-            .split(",")
-            .map(|group_str| group_str.parse::<usize>().unwrap())
-            .collect();
-        total += count_possible_combinations(record.as_bytes(), &groups, &mut HashMap::new());
+        total += count_for_line(&line, false);
     }
 
-    println!("total: {}", total);
+    return total;
+}
+
+pub fn step_1(lines: impl IntoIterator<Item = String>) {
+    println!("total: {}", solve_step_1(lines));
 }
 
-pub fn step_2(lines: impl IntoIterator<Item = String>) {
+pub fn solve_step_2(lines: impl IntoIterator<Item = String>) -> u64 {
     let mut total = 0;
 
     for line in lines {
-        let split: Vec<&str> = line.split(" ").collect();
-        let record = split[0];
-        let groups: Vec<usize> = split[1]
-            .split(",")
-            .map(|group_str| group_str.parse::<usize>().unwrap())
-            .collect();
+        total += count_for_line(&line, true);
+    }
+
+    return total;
+}
+
+pub fn step_2(lines: impl IntoIterator<Item = String>) {
+    println!("total: {}", solve_step_2(lines));
+}
 
-        let mut joined_record = record.to_owned();
-        let mut joined_groups = groups.clone();
-        for _ in 0..4 {
-            joined_record += "?";
-            joined_record += record;
-            joined_groups = joined_groups
-                .iter()
-                .chain(&groups)
-                .map(|ptr| *ptr)
-                .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrangements_match_count() {
+        // arrange
+        let lines = crate::examples::example_lines(12, 1).unwrap();
+
+        for line in lines {
+            // act
+            let arrangements: Vec<String> = get_arrangements(&line, false).collect();
+
+            // assert
+            let (record, groups) = parse_line(&line, false);
+            assert_eq!(arrangements.len() as u64, count_for_line(&line, false));
+            for arrangement in &arrangements {
+                assert!(arrangement
+                    .bytes()
+                    .zip(record.iter())
+                    .all(|(byte, record_byte)| *record_byte == b'?' || byte == *record_byte));
+                let arrangement_groups: Vec<usize> = arrangement
+                    .split('.')
+                    .filter(|group| !group.is_empty())
+                    .map(|group| group.len())
+                    .collect();
+                assert_eq!(arrangement_groups, groups);
+            }
         }
-        total += count_possible_combinations(
-            joined_record.as_bytes(),
-            &joined_groups,
-            &mut HashMap::new(),
-        );
     }
 
-    println!("total: {}", total);
+    #[test]
+    fn test_probabilities() {
+        // arrange
+        let line = ".??..??...?##. 1,1,3";
+
+        // act
+        let result = get_probabilities(line, false);
+
+        // assert
+        assert_eq!(
+            result,
+            vec![(1, 0.5), (2, 0.5), (5, 0.5), (6, 0.5), (10, 1.0)]
+        );
+    }
 }
@@ -4,10 +4,17 @@ fn is_power_of_2(value: u64) -> bool {
     return value != 0 && value & (value - 1) == 0;
 }
 
-fn find_mirror(sequence: &Vec<u64>, is_step_2: bool) -> usize {
+// `axis` is how many rows or columns come before the mirror. in step 2, `smudge` is the pair of
+// indices that differ by one cell, along with the bit that differs
+struct Mirror {
+    axis: usize,
+    smudge: Option<(usize, usize, u64)>,
+}
+
+fn find_mirror(sequence: &[u64], is_step_2: bool) -> Option<Mirror> {
     for candidate in 1..sequence.len() {
         let mut is_mirror = true;
-        let mut found_smudge = false;
+        let mut smudge: Option<(usize, usize, u64)> = None;
         for distance in 0..candidate {
             let high_index = candidate + distance;
             if high_index >= sequence.len() {
@@ -18,30 +25,45 @@ fn find_mirror(sequence: &Vec<u64>, is_step_2: bool) -> usize {
                 continue;
             }
             if is_step_2
-                && !found_smudge
+                && smudge.is_none()
                 && is_power_of_2(sequence[low_index] ^ sequence[high_index])
             {
-                found_smudge = true;
+                smudge = Some((
+                    low_index,
+                    high_index,
+                    sequence[low_index] ^ sequence[high_index],
+                ));
                 continue;
             }
             is_mirror = false;
             break;
         }
 
-        if is_mirror && (!is_step_2 || found_smudge) {
-            return candidate;
+        if is_mirror && (!is_step_2 || smudge.is_some()) {
+            return Some(Mirror {
+                axis: candidate,
+                smudge,
+            });
         }
     }
 
-    return 0;
+    return None;
 }
 
-fn execute(lines: impl IntoIterator<Item = String>, is_step_2: bool) {
-    let mut total = 0;
+struct Pattern {
+    lines: Vec<String>,
+    // each row and column as a bit mask, with # as 1 and the first byte as the highest bit
+    rows: Vec<u64>,
+    columns: Vec<u64>,
+}
+
+fn parse_patterns(lines: impl IntoIterator<Item = String>) -> Vec<Pattern> {
+    let mut patterns: Vec<Pattern> = Vec::new();
 
     let mut lines_iterator = lines.into_iter();
     let mut reached_end = false;
     while !reached_end {
+        let mut pattern_lines: Vec<String> = Vec::new();
         let mut rows: Vec<u64> = Vec::new();
         let mut columns: Vec<u64> = Vec::new();
 
@@ -65,19 +87,219 @@ fn execute(lines: impl IntoIterator<Item = String>, is_step_2: bool) {
                 columns[byte_index] += byte_value;
             }
 
+            pattern_lines.push(line);
             line_index += 1;
         }
 
-        total += 100 * find_mirror(&rows, is_step_2) + find_mirror(&columns, is_step_2);
+        patterns.push(Pattern {
+            lines: pattern_lines,
+            rows,
+            columns,
+        });
+    }
+
+    return patterns;
+}
+
+fn execute(lines: impl IntoIterator<Item = String>, is_step_2: bool) -> usize {
+    let mut total = 0;
+    for pattern in parse_patterns(lines) {
+        let get_axis = |mirror: Option<Mirror>| mirror.map_or(0, |mirror| mirror.axis);
+        total += 100 * get_axis(find_mirror(&pattern.rows, is_step_2))
+            + get_axis(find_mirror(&pattern.columns, is_step_2));
     }
 
-    println!("total: {}", total);
+    return total;
+}
+
+pub fn solve_step_1(lines: impl IntoIterator<Item = String>) -> usize {
+    return execute(lines, false);
 }
 
 pub fn step_1(lines: impl IntoIterator<Item = String>) {
-    execute(lines, false);
+    println!("total: {}", solve_step_1(lines));
+}
+
+pub fn solve_step_2(lines: impl IntoIterator<Item = String>) -> usize {
+    return execute(lines, true);
 }
 
 pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    execute(lines, true);
+    println!("total: {}", solve_step_2(lines));
+}
+
+struct Reflection {
+    row_mirror: Option<Mirror>,
+    column_mirror: Option<Mirror>,
+    // the line and byte of the smudged cell
+    smudge: Option<(usize, usize)>,
+}
+
+fn find_reflection(pattern: &Pattern, is_step_2: bool) -> Reflection {
+    let row_mirror = find_mirror(&pattern.rows, is_step_2);
+    let column_mirror = find_mirror(&pattern.columns, is_step_2);
+    let width = pattern.columns.len();
+    let height = pattern.rows.len();
+
+    // the bits are numbered from the end of the row or column
+    let smudge: Option<(usize, usize)> = match (&row_mirror, &column_mirror) {
+        (
+            Some(Mirror {
+                smudge: Some((line_index, _, bit)),
+                ..
+            }),
+            _,
+        ) => Some((*line_index, width - 1 - bit.trailing_zeros() as usize)),
+        (
+            _,
+            Some(Mirror {
+                smudge: Some((byte_index, _, bit)),
+                ..
+            }),
+        ) => Some((height - 1 - bit.trailing_zeros() as usize, *byte_index)),
+        _ => None,
+    };
+
+    return Reflection {
+        row_mirror,
+        column_mirror,
+        smudge,
+    };
+}
+
+const ANSI_SMUDGE: &str = "\x1b[1;7m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+// patterns with no mirror are called out, since they quietly count for nothing in the total
+pub fn print_reflections(lines: impl IntoIterator<Item = String>, step: u32) {
+    let is_step_2 = step == 2;
+    let mut num_without_mirror = 0;
+    for (pattern_index, pattern) in parse_patterns(lines).into_iter().enumerate() {
+        let Reflection {
+            row_mirror,
+            column_mirror,
+            smudge,
+        } = find_reflection(&pattern, is_step_2);
+        let width = pattern.columns.len();
+
+        println!("pattern {}:", pattern_index + 1);
+        let column_markers = column_mirror.as_ref().map(|mirror| {
+            let mut markers = " ".repeat(width);
+            markers.replace_range((mirror.axis - 1)..(mirror.axis + 1), "><");
+            markers
+        });
+        if let Some(markers) = &column_markers {
+            println!(" {} ", markers);
+        }
+        for (line_index, line) in pattern.lines.iter().enumerate() {
+            let marker = match &row_mirror {
+                Some(mirror) if line_index + 1 == mirror.axis => 'v',
+                Some(mirror) if line_index == mirror.axis => '^',
+                _ => ' ',
+            };
+            let mut drawn_line = String::new();
+            for (byte_index, byte) in line.bytes().enumerate() {
+                if smudge == Some((line_index, byte_index)) {
+                    drawn_line += &format!("{}{}{}", ANSI_SMUDGE, byte as char, ANSI_RESET);
+                } else {
+                    drawn_line.push(byte as char);
+                }
+            }
+            println!("{}{}{}", marker, drawn_line, marker);
+        }
+        if let Some(markers) = &column_markers {
+            println!(" {} ", markers);
+        }
+
+        match (&row_mirror, &column_mirror) {
+            (None, None) => {
+                num_without_mirror += 1;
+                println!("no reflection found, so this pattern adds 0");
+            }
+            _ => {
+                if let Some(mirror) = &row_mirror {
+                    println!("{} rows above the mirror", mirror.axis);
+                }
+                if let Some(mirror) = &column_mirror {
+                    println!("{} columns left of the mirror", mirror.axis);
+                }
+            }
+        }
+        if let Some((line_index, byte_index)) = smudge {
+            println!("smudge at line {}, byte {}", line_index, byte_index);
+        }
+        println!();
+    }
+
+    if num_without_mirror > 0 {
+        println!("{} patterns have no reflection", num_without_mirror);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the row axis, the column axis and the smudge
+    type Summary = (usize, usize, Option<(usize, usize)>);
+
+    fn describe(lines: Vec<String>, is_step_2: bool) -> Vec<Summary> {
+        return parse_patterns(lines)
+            .iter()
+            .map(|pattern| {
+                let reflection = find_reflection(pattern, is_step_2);
+                let get_axis = |mirror: Option<Mirror>| mirror.map_or(0, |mirror| mirror.axis);
+                (
+                    get_axis(reflection.row_mirror),
+                    get_axis(reflection.column_mirror),
+                    reflection.smudge,
+                )
+            })
+            .collect();
+    }
+
+    #[test]
+    fn test_example_reflections() {
+        // arrange
+        // cleaning either cell of the mismatched pair works, and the upper or left one is reported
+        let lines = crate::examples::example_lines(13, 1).unwrap();
+
+        // act
+        let result = (describe(lines.clone(), false), describe(lines, true));
+
+        // assert
+        assert_eq!(
+            result,
+            (
+                vec![(0, 5, None), (4, 0, None)],
+                vec![(3, 0, Some((0, 0))), (1, 0, Some((0, 4)))]
+            )
+        );
+    }
+
+    #[test]
+    fn test_smudge_in_a_column_mirror() {
+        // arrange
+        // the pattern only reflects between its 2nd and 3rd columns once one of the cells in the
+        // middle of the last line is flipped
+        let lines = vec!["#..#".to_owned(), ".##.".to_owned(), "#.##".to_owned()];
+
+        // act
+        let result = describe(lines, true);
+
+        // assert
+        assert_eq!(result, vec![(0, 2, Some((2, 1)))]);
+    }
+
+    #[test]
+    fn test_pattern_without_reflection() {
+        // arrange
+        let lines = vec!["#..".to_owned(), "..#".to_owned(), "##.".to_owned()];
+
+        // act
+        let result = describe(lines, false);
+
+        // assert
+        assert_eq!(result, vec![(0, 0, None)]);
+    }
 }
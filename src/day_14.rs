@@ -1,4 +1,7 @@
+use crate::render::{Canvas, HIGHLIGHT, WALL};
 use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
 use std::vec::Vec;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -60,7 +63,8 @@ enum Direction {
     West,
 }
 
-struct Dish {
+#[derive(Clone)]
+pub struct Dish {
     lines: Vec<Vec<u8>>,
 }
 
@@ -111,65 +115,59 @@ impl Dish {
             }
         }
     }
-}
+    fn tilt(&mut self, direction: Direction) {
+        let (num_rows, num_columns) = self.size(direction);
 
-pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    let mut dish = Dish {
-        lines: lines.into_iter().map(|line| line.into_bytes()).collect(),
-    };
+        let mut working_groups: Vec<Group> = (0..num_columns)
+            .map(|column_index| Group {
+                column_index,
+                last_resting_point: 0,
+                num_rocks: 0,
+            })
+            .collect();
 
-    let mut visited_map: HashMap<String, u64> = HashMap::new();
-    let mut visited_vec: Vec<String> = Vec::new();
+        for row_index in 0..num_rows {
+            for column_index in 0..num_columns {
+                let byte = self.get(direction, row_index, column_index);
+                match byte {
+                    b'O' => working_groups[column_index].num_rocks += 1,
+                    b'#' => {
+                        if working_groups[column_index].num_rocks > 0 {
+                            self.process_group(direction, working_groups[column_index]);
+                        }
+                        working_groups[column_index] = Group {
+                            column_index,
+                            last_resting_point: row_index + 1,
+                            num_rocks: 0,
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
 
-    let mut i = 0;
-    let loop_start: u64;
-    let loop_end: u64;
-    loop {
+        for group in working_groups {
+            if group.num_rocks > 0 {
+                self.process_group(direction, group);
+            }
+        }
+    }
+
+    fn cycle(&mut self, mut on_tilt: impl FnMut(&Dish, Direction)) {
         for direction in [
             Direction::North,
             Direction::West,
             Direction::South,
             Direction::East,
         ] {
-            let (num_rows, num_columns) = dish.size(direction);
-
-            let mut working_groups: Vec<Group> = (0..num_columns)
-                .map(|column_index| Group {
-                    column_index,
-                    last_resting_point: 0,
-                    num_rocks: 0,
-                })
-                .collect();
-
-            for row_index in 0..num_rows {
-                for column_index in 0..num_columns {
-                    let byte = dish.get(direction, row_index, column_index);
-                    match byte {
-                        b'O' => working_groups[column_index].num_rocks += 1,
-                        b'#' => {
-                            if working_groups[column_index].num_rocks > 0 {
-                                dish.process_group(direction, working_groups[column_index]);
-                            }
-                            working_groups[column_index] = Group {
-                                column_index,
-                                last_resting_point: row_index + 1,
-                                num_rocks: 0,
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-
-            for group in working_groups {
-                if group.num_rocks > 0 {
-                    dish.process_group(direction, group);
-                }
-            }
+            self.tilt(direction);
+            on_tilt(self, direction);
         }
+    }
 
+    fn as_string(&self) -> String {
         let mut as_string = "".to_owned();
-        for line in &dish.lines {
+        for line in &self.lines {
             if as_string != "" {
                 as_string += "\n";
             }
@@ -178,11 +176,42 @@ pub fn step_2(lines: impl IntoIterator<Item = String>) {
                 .map(|byte| *byte as char)
                 .collect::<String>();
         }
+        return as_string;
+    }
+
+    fn from_string(as_string: &str) -> Dish {
+        return Dish {
+            lines: as_string
+                .split("\n")
+                .map(|line| line.as_bytes().to_vec())
+                .collect(),
+        };
+    }
+}
 
+pub fn parse_dish(lines: impl IntoIterator<Item = String>) -> Dish {
+    return Dish {
+        lines: lines.into_iter().map(|line| line.into_bytes()).collect(),
+    };
+}
+
+// returns the state after each cycle, and the first repeated state and the cycle that repeated
+// it if there was a loop. `on_tilt` gets the cycle index after every tilt
+fn find_loop(
+    mut dish: Dish,
+    num_cycles: u64,
+    mut on_tilt: impl FnMut(&Dish, u64, Direction),
+) -> (Vec<String>, Option<(u64, u64)>) {
+    let mut visited_map: HashMap<String, u64> = HashMap::new();
+    let mut visited_vec: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    while i < num_cycles {
+        dish.cycle(|dish, direction| on_tilt(dish, i, direction));
+
+        let as_string = dish.as_string();
         if let Some(prior_index) = visited_map.get(&as_string) {
-            loop_start = *prior_index;
-            loop_end = i;
-            break;
+            return (visited_vec, Some((*prior_index, i)));
         }
         visited_map.insert(as_string.clone(), i);
         visited_vec.push(as_string);
@@ -190,17 +219,231 @@ pub fn step_2(lines: impl IntoIterator<Item = String>) {
         i += 1;
     }
 
-    let loop_length = loop_end - loop_start;
-    let final_index = ((1000000000 - 1 - loop_start) % loop_length) + loop_start;
+    return (visited_vec, None);
+}
+
+fn get_final_index(num_cycles: u64, loop_bounds: Option<(u64, u64)>) -> u64 {
+    return match loop_bounds {
+        // if we ran out of cycles before finding a loop, the last state is the answer
+        None => num_cycles - 1,
+        Some((loop_start, loop_end)) => {
+            let loop_length = loop_end - loop_start;
+            ((num_cycles - 1 - loop_start) % loop_length) + loop_start
+        }
+    };
+}
+
+pub fn run_cycles(dish: Dish, num_cycles: u64) -> Dish {
+    if num_cycles == 0 {
+        return dish;
+    }
+
+    let (visited_vec, loop_bounds) = find_loop(dish, num_cycles, |_, _, _| {});
+    let final_index = get_final_index(num_cycles, loop_bounds);
+    return Dish::from_string(&visited_vec[final_index as usize]);
+}
+
+pub fn get_load_after_cycles(dish: Dish, num_cycles: u64) -> usize {
+    return get_north_load(&run_cycles(dish, num_cycles));
+}
 
+fn get_north_load(dish: &Dish) -> usize {
     let mut load = 0;
-    for (line_index, line) in visited_vec[final_index as usize].split("\n").enumerate() {
-        for byte in line.bytes() {
-            if byte == b'O' {
+    for (line_index, line) in dish.lines.iter().enumerate() {
+        for byte in line {
+            if *byte == b'O' {
                 load += dish.lines.len() - line_index;
             }
         }
     }
+    return load;
+}
+
+pub const NUM_CYCLES: u64 = 1000000000;
+
+pub fn step_2(lines: impl IntoIterator<Item = String>) {
+    let dish = parse_dish(lines);
+    println!("{}", get_load_after_cycles(dish, NUM_CYCLES));
+}
+
+pub fn render(lines: Vec<String>, step: u32, num_cycles: u64) -> Canvas {
+    let mut dish = parse_dish(lines);
+    if step == 1 {
+        dish.tilt(Direction::North);
+    } else {
+        dish = run_cycles(dish, num_cycles);
+    }
+
+    let width = dish.lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let mut canvas = Canvas::new(width, dish.lines.len());
+    for (line_index, line) in dish.lines.iter().enumerate() {
+        for (byte_index, byte) in line.iter().enumerate() {
+            match byte {
+                b'O' => canvas.set(line_index, byte_index, HIGHLIGHT),
+                b'#' => canvas.set(line_index, byte_index, WALL),
+                _ => {}
+            }
+        }
+    }
+    return canvas;
+}
+
+const ANSI_CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+const ANSI_ROCK: &str = "\x1b[1;33m";
+const ANSI_LOOP: &str = "\x1b[7m";
+const ANSI_RESET: &str = "\x1b[0m";
 
-    println!("{}", load);
+fn draw_dish(dish: &Dish) {
+    for line in &dish.lines {
+        let mut drawn_line = String::new();
+        for byte in line {
+            if *byte == b'O' {
+                drawn_line += &format!("{}O{}", ANSI_ROCK, ANSI_RESET);
+            } else {
+                drawn_line.push(*byte as char);
+            }
+        }
+        println!("{}", drawn_line);
+    }
+}
+
+pub fn animate(lines: impl IntoIterator<Item = String>, frame_delay: Duration) {
+    let dish = parse_dish(lines);
+
+    let (visited_vec, loop_bounds) = find_loop(dish, NUM_CYCLES, |dish, i, direction| {
+        print!("{}", ANSI_CLEAR_SCREEN);
+        draw_dish(dish);
+        println!();
+        println!(
+            "cycle {} ({:?}), north load {}",
+            i + 1,
+            direction,
+            get_north_load(dish)
+        );
+        thread::sleep(frame_delay);
+    });
+    let final_index = get_final_index(NUM_CYCLES, loop_bounds);
+
+    println!();
+    for (index, as_string) in visited_vec.iter().enumerate() {
+        let index = index as u64;
+        let in_loop = match loop_bounds {
+            Some((loop_start, loop_end)) => index >= loop_start && index < loop_end,
+            None => false,
+        };
+        let line = format!(
+            "cycle {:>4}: north load {}{}",
+            index + 1,
+            get_north_load(&Dish::from_string(as_string)),
+            if index == final_index {
+                " <- final"
+            } else {
+                ""
+            }
+        );
+        if in_loop {
+            println!("{}{}{}", ANSI_LOOP, line, ANSI_RESET);
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    // everything here is 0-based like in find_loop, so cycle number n is index n - 1
+    match loop_bounds {
+        Some((loop_start, loop_end)) => {
+            println!(
+                "index {} repeats index {}, so the loop is indices {}..{} (length {})",
+                loop_end,
+                loop_start,
+                loop_start,
+                loop_end,
+                loop_end - loop_start
+            );
+            println!(
+                "final_index = (({} - 1 - {}) % {}) + {} = {}",
+                NUM_CYCLES,
+                loop_start,
+                loop_end - loop_start,
+                loop_start,
+                final_index
+            );
+        }
+        None => println!("no loop found, final_index = {}", final_index),
+    }
+    println!(
+        "load after {} cycles: {}",
+        NUM_CYCLES,
+        get_north_load(&Dish::from_string(&visited_vec[final_index as usize]))
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples;
+
+    fn canvas_to_lines(canvas: &Canvas, width: usize, height: usize) -> Vec<String> {
+        return (0..height)
+            .map(|row| {
+                (0..width)
+                    .map(|column| match canvas.get(row, column) {
+                        HIGHLIGHT => 'O',
+                        WALL => '#',
+                        _ => '.',
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    #[test]
+    fn test_render_after_cycles() {
+        // arrange
+        let lines = examples::example_lines(14, 2).unwrap();
+        let expected = [
+            ".....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....",
+            ".....#....
+....#...O#
+.....##...
+..O#......
+.....OOO#.
+.O#...O#.#
+....O#...O
+.......OOO
+#..OO###..
+#.OOO#...O",
+            ".....#....
+....#...O#
+.....##...
+..O#......
+.....OOO#.
+.O#...O#.#
+....O#...O
+.......OOO
+#...O###.O
+#.OOO#...O",
+        ];
+
+        for (num_cycles, expected) in (1..=3).zip(expected) {
+            // act
+            let canvas = render(lines.clone(), 2, num_cycles);
+
+            // assert
+            assert_eq!(
+                canvas_to_lines(&canvas, 10, 10).join("\n"),
+                expected,
+                "after {} cycles",
+                num_cycles
+            );
+        }
+    }
 }
@@ -1,4 +1,5 @@
-use std::collections::HashSet;
+use crate::render::{Canvas, ACCENT, HIGHLIGHT, WALL};
+use std::collections::{HashMap, HashSet};
 use std::vec::Vec;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
@@ -25,12 +26,12 @@ fn move_in_direction(
     return (new_line_index, new_byte_index, direction);
 }
 
-fn execute(
-    layout: &Vec<Vec<u8>>,
+fn trace_beam(
+    layout: &[Vec<u8>],
     start_line_index: i32,
     start_byte_index: i32,
     start_direction: Direction,
-) -> usize {
+) -> HashSet<(i32, i32, Direction)> {
     let mut beams: Vec<(i32, i32, Direction)> = Vec::new();
     beams.push((start_line_index, start_byte_index, start_direction));
 
@@ -99,7 +100,16 @@ fn execute(
         }
     }
 
-    return energized
+    return energized;
+}
+
+fn execute(
+    layout: &[Vec<u8>],
+    start_line_index: i32,
+    start_byte_index: i32,
+    start_direction: Direction,
+) -> usize {
+    return trace_beam(layout, start_line_index, start_byte_index, start_direction)
         .into_iter()
         .map(|(x, y, _)| (x, y))
         .collect::<HashSet<_>>()
@@ -112,39 +122,221 @@ pub fn step_1(lines: impl IntoIterator<Item = String>) {
     println!("result: {}", execute(&layout, 0, 0, Direction::East));
 }
 
-pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    let layout: Vec<Vec<u8>> = lines.into_iter().map(|line| line.into_bytes()).collect();
-    let mut max_value = 0;
+fn get_entry_points(layout: &[Vec<u8>]) -> Vec<(i32, i32, Direction)> {
+    let mut entry_points: Vec<(i32, i32, Direction)> = Vec::new();
     for (line_index, line) in layout.iter().enumerate() {
-        max_value = std::cmp::max(
-            max_value,
-            execute(&layout, line_index as i32, 0, Direction::East),
-        );
-        max_value = std::cmp::max(
-            max_value,
-            execute(
-                &layout,
-                line_index as i32,
-                line.len() as i32 - 1,
-                Direction::West,
-            ),
-        );
+        entry_points.push((line_index as i32, 0, Direction::East));
+        entry_points.push((line_index as i32, line.len() as i32 - 1, Direction::West));
     }
     for (byte_index, _) in layout[0].iter().enumerate() {
-        max_value = std::cmp::max(
-            max_value,
-            execute(&layout, 0, byte_index as i32, Direction::South),
-        );
-        max_value = std::cmp::max(
-            max_value,
-            execute(
-                &layout,
-                layout.len() as i32 - 1,
-                byte_index as i32,
-                Direction::North,
-            ),
-        );
+        entry_points.push((0, byte_index as i32, Direction::South));
+        entry_points.push((layout.len() as i32 - 1, byte_index as i32, Direction::North));
     }
+    return entry_points;
+}
+
+// ties go to the first entry point
+fn find_best_entry_point(layout: &[Vec<u8>]) -> ((i32, i32, Direction), usize) {
+    let mut best_entry_point = (0, 0, Direction::East);
+    let mut max_value = 0;
+    for (line_index, byte_index, direction) in get_entry_points(layout) {
+        let value = execute(layout, line_index, byte_index, direction);
+        if value > max_value {
+            max_value = value;
+            best_entry_point = (line_index, byte_index, direction);
+        }
+    }
+    return (best_entry_point, max_value);
+}
+
+fn describe_entry_point((line_index, byte_index, direction): (i32, i32, Direction)) -> String {
+    let edge = match direction {
+        Direction::East => "left",
+        Direction::West => "right",
+        Direction::South => "top",
+        Direction::North => "bottom",
+    };
+    return format!(
+        "line {}, byte {} on the {} edge, heading {:?}",
+        line_index, byte_index, edge, direction
+    );
+}
+
+pub fn step_2(lines: impl IntoIterator<Item = String>) {
+    let layout: Vec<Vec<u8>> = lines.into_iter().map(|line| line.into_bytes()).collect();
+    let (best_entry_point, max_value) = find_best_entry_point(&layout);
 
     println!("result: {}", max_value);
+    println!("best entry: {}", describe_entry_point(best_entry_point));
+}
+
+pub fn render(lines: Vec<String>, step: u32) -> Canvas {
+    let layout: Vec<Vec<u8>> = lines.into_iter().map(|line| line.into_bytes()).collect();
+    let (line_index, byte_index, direction) = if step == 1 {
+        (0, 0, Direction::East)
+    } else {
+        find_best_entry_point(&layout).0
+    };
+    let energized: HashSet<(i32, i32)> = trace_beam(&layout, line_index, byte_index, direction)
+        .into_iter()
+        .map(|(x, y, _)| (x, y))
+        .collect();
+
+    let mut canvas = Canvas::new(layout[0].len(), layout.len());
+    for (line_index, line) in layout.iter().enumerate() {
+        for (byte_index, byte) in line.iter().enumerate() {
+            let is_energized = energized.contains(&(line_index as i32, byte_index as i32));
+            let color = match (*byte == b'.', is_energized) {
+                (true, true) => HIGHLIGHT,
+                (true, false) => continue,
+                (false, true) => ACCENT,
+                (false, false) => WALL,
+            };
+            canvas.set(line_index, byte_index, color);
+        }
+    }
+    return canvas;
+}
+
+fn get_arrow(direction: Direction) -> char {
+    return match direction {
+        Direction::North => '↑',
+        Direction::South => '↓',
+        Direction::East => '→',
+        Direction::West => '←',
+    };
+}
+
+// mirrors and splitters are left as they are, so that the beams can still be followed
+fn draw_heatmap(
+    layout: &[Vec<u8>],
+    directions: &HashMap<(i32, i32), Vec<Direction>>,
+) -> Vec<String> {
+    let mut drawn_lines: Vec<String> = Vec::new();
+    for (line_index, line) in layout.iter().enumerate() {
+        let mut drawn_line = String::new();
+        for (byte_index, byte) in line.iter().enumerate() {
+            let tile_directions = directions
+                .get(&(line_index as i32, byte_index as i32))
+                .map(|directions| directions.as_slice());
+            drawn_line.push(match tile_directions {
+                Some([direction]) if *byte == b'.' => get_arrow(*direction),
+                Some(directions) if *byte == b'.' => {
+                    char::from_digit(directions.len() as u32, 10).unwrap()
+                }
+                _ => *byte as char,
+            });
+        }
+        drawn_lines.push(drawn_line);
+    }
+    return drawn_lines;
+}
+
+pub fn print_heatmap(lines: impl IntoIterator<Item = String>, step: u32) {
+    let layout: Vec<Vec<u8>> = lines.into_iter().map(|line| line.into_bytes()).collect();
+    let (entry_point, _) = if step == 1 {
+        ((0, 0, Direction::East), 0)
+    } else {
+        find_best_entry_point(&layout)
+    };
+    let (line_index, byte_index, direction) = entry_point;
+
+    let mut directions: HashMap<(i32, i32), Vec<Direction>> = HashMap::new();
+    for (x, y, direction) in trace_beam(&layout, line_index, byte_index, direction) {
+        directions.entry((x, y)).or_default().push(direction);
+    }
+
+    for drawn_line in draw_heatmap(&layout, &directions) {
+        println!("{}", drawn_line);
+    }
+
+    println!();
+    println!("energized: {}", directions.len());
+    println!(
+        "passes: {} in one direction, {} in more than one",
+        directions
+            .values()
+            .filter(|directions| directions.len() == 1)
+            .count(),
+        directions
+            .values()
+            .filter(|directions| directions.len() > 1)
+            .count()
+    );
+    println!("entry: {}", describe_entry_point(entry_point));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_layout(lines: &[&str]) -> Vec<Vec<u8>> {
+        return lines.iter().map(|line| line.as_bytes().to_vec()).collect();
+    }
+
+    #[test]
+    fn test_best_entry_point_for_example() {
+        // arrange
+        let layout: Vec<Vec<u8>> = crate::examples::example_lines(16, 2)
+            .unwrap()
+            .into_iter()
+            .map(|line| line.into_bytes())
+            .collect();
+
+        // act
+        let result = find_best_entry_point(&layout);
+
+        // assert
+        assert_eq!(result, ((0, 3, Direction::South), 51));
+    }
+
+    #[test]
+    fn test_trace_beam_splits_and_reflects() {
+        // arrange
+        let layout = parse_layout(&[".|.", "...", ".\\/"]);
+
+        // act
+        let result = trace_beam(&layout, 0, 0, Direction::East);
+
+        // assert
+        let expected: HashSet<(i32, i32, Direction)> = HashSet::from([
+            (0, 0, Direction::East),
+            (0, 1, Direction::East),
+            (1, 1, Direction::South),
+            (2, 1, Direction::South),
+            (2, 2, Direction::East),
+            (1, 2, Direction::North),
+            (0, 2, Direction::North),
+        ]);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_trace_beam_stops_on_loops() {
+        // arrange
+        // the beam goes round the edge and comes back to where it started
+        let layout = parse_layout(&["/.\\", "...", "\\./"]);
+
+        // act
+        let result = trace_beam(&layout, 0, 1, Direction::East);
+
+        // assert
+        assert_eq!(result.len(), 8);
+    }
+
+    #[test]
+    fn test_heatmap_keeps_mirrors() {
+        // arrange
+        let layout = parse_layout(&[".|.", "...", ".\\/"]);
+        let mut directions: HashMap<(i32, i32), Vec<Direction>> = HashMap::new();
+        for (x, y, direction) in trace_beam(&layout, 0, 0, Direction::East) {
+            directions.entry((x, y)).or_default().push(direction);
+        }
+
+        // act
+        let result = draw_heatmap(&layout, &directions);
+
+        // assert
+        assert_eq!(result, vec!["→|↑", ".↓↑", ".\\/"]);
+    }
 }
@@ -1,3 +1,5 @@
+use crate::render::{blend, Canvas, ACCENT, BACKGROUND, WALL};
+use std::collections::HashMap;
 use std::vec::Vec;
 use std::{cmp::Ordering, collections::BTreeSet};
 
@@ -18,6 +20,9 @@ fn opposite_direction(direction: Direction) -> Direction {
     };
 }
 
+// everything about a position except its cost
+type State = (usize, usize, Direction, u8);
+
 #[derive(Eq)]
 struct Position {
     x: usize,
@@ -25,7 +30,19 @@ struct Position {
     last_direction: Direction,
     last_direction_count: u8,
     cost: u64,
-    // history: Vec<Direction>,
+    // not part of the ordering, so positions that only differ by how we got there are equal
+    previous: Option<State>,
+}
+
+impl Position {
+    fn state(&self) -> State {
+        return (
+            self.x,
+            self.y,
+            self.last_direction,
+            self.last_direction_count,
+        );
+    }
 }
 
 impl Ord for Position {
@@ -62,12 +79,19 @@ impl PartialEq for Position {
     }
 }
 
-pub fn step_1(lines: impl IntoIterator<Item = String>) {
-    let layout: Vec<Vec<u64>> = lines
+pub fn parse_layout(lines: impl IntoIterator<Item = String>) -> Vec<Vec<u64>> {
+    return lines
         .into_iter()
         .map(|line| line.bytes().map(|byte| (byte - b'0') as u64).collect())
         .collect();
+}
 
+// returns the heat loss along with every (x, y) on the path, starting from the top left
+pub fn find_minimum_heat_loss(
+    layout: &[Vec<u64>],
+    min_run: u8,
+    max_run: u8,
+) -> Option<(u64, Vec<(usize, usize)>)> {
     let height = layout.len();
     let width = layout[0].len();
 
@@ -78,24 +102,40 @@ pub fn step_1(lines: impl IntoIterator<Item = String>) {
         last_direction: Direction::East,
         last_direction_count: 0,
         cost: 0,
-        // history: Vec::new(),
+        previous: None,
+    });
+    positions.insert(Position {
+        x: 0,
+        y: 0,
+        last_direction: Direction::South,
+        last_direction_count: 0,
+        cost: 0,
+        previous: None,
     });
 
+    // the first time we pop a state is the cheapest way to reach it, so that's the only time we
+    // need to remember where it came from or expand it
+    let mut predecessors: HashMap<State, Option<State>> = HashMap::new();
+
     while let Some(position) = positions.pop_first() {
-        if position.x == width - 1 && position.y == height - 1 {
-            println!("result: {}", position.cost);
-            // for direction in position.history {
-            //     println!(
-            //         "{}",
-            //         match direction {
-            //             Direction::North => "N",
-            //             Direction::South => "S",
-            //             Direction::East => "E",
-            //             Direction::West => "W",
-            //         }
-            //     )
-            // }
-            return;
+        if predecessors.contains_key(&position.state()) {
+            continue;
+        }
+        predecessors.insert(position.state(), position.previous);
+
+        // the crucible can't stop at the end until it has moved far enough to be able to turn
+        if position.x == width - 1
+            && position.y == height - 1
+            && position.last_direction_count >= min_run
+        {
+            let mut path: Vec<(usize, usize)> = Vec::new();
+            let mut current = Some(position.state());
+            while let Some(state) = current {
+                path.push((state.0, state.1));
+                current = predecessors[&state];
+            }
+            path.reverse();
+            return Some((position.cost, path));
         }
 
         for (new_x, new_y, direction) in [
@@ -108,13 +148,13 @@ pub fn step_1(lines: impl IntoIterator<Item = String>) {
                 || new_x as usize >= width
                 || new_y < 0
                 || new_y as usize >= height
-                || (position.last_direction == direction && position.last_direction_count == 3)
+                || (position.last_direction == direction
+                    && position.last_direction_count == max_run)
+                || (position.last_direction != direction && position.last_direction_count < min_run)
                 || direction == opposite_direction(position.last_direction)
             {
                 continue;
             }
-            // let mut history = position.history.clone();
-            // history.push(direction);
             positions.insert(Position {
                 x: new_x as usize,
                 y: new_y as usize,
@@ -125,90 +165,271 @@ pub fn step_1(lines: impl IntoIterator<Item = String>) {
                     1
                 },
                 cost: position.cost + layout[new_y as usize][new_x as usize],
-                // history,
+                previous: Some(position.state()),
             });
         }
     }
 
-    println!("did not get to end");
+    return None;
+}
+
+fn print_result(result: Option<(u64, Vec<(usize, usize)>)>) {
+    match result {
+        Some((cost, _)) => println!("result: {}", cost),
+        None => println!("did not get to end"),
+    }
+}
+
+pub fn step_1(lines: impl IntoIterator<Item = String>) {
+    let layout = parse_layout(lines);
+    print_result(find_minimum_heat_loss(&layout, 1, 3));
 }
 
 pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    let layout: Vec<Vec<u64>> = lines
-        .into_iter()
-        .map(|line| line.bytes().map(|byte| (byte - b'0') as u64).collect())
-        .collect();
+    let layout = parse_layout(lines);
+    print_result(find_minimum_heat_loss(&layout, 4, 10));
+}
 
-    let height = layout.len();
-    let width = layout[0].len();
+fn get_direction_between(from: (usize, usize), to: (usize, usize)) -> Direction {
+    return match (to.0 as i64 - from.0 as i64, to.1 as i64 - from.1 as i64) {
+        (0, -1) => Direction::North,
+        (0, 1) => Direction::South,
+        (1, 0) => Direction::East,
+        (-1, 0) => Direction::West,
+        _ => panic!("route has a gap"),
+    };
+}
 
-    let mut positions: BTreeSet<Position> = BTreeSet::new();
-    positions.insert(Position {
-        x: 0,
-        y: 0,
-        last_direction: Direction::East,
-        last_direction_count: 0,
-        cost: 0,
-        // history: Vec::new(),
-    });
-    positions.insert(Position {
-        x: 0,
-        y: 0,
-        last_direction: Direction::South,
-        last_direction_count: 0,
-        cost: 0,
-        // history: Vec::new(),
-    });
+fn get_arrow(direction: Direction) -> char {
+    return match direction {
+        Direction::North => '^',
+        Direction::South => 'v',
+        Direction::East => '>',
+        Direction::West => '<',
+    };
+}
 
-    while let Some(position) = positions.pop_first() {
-        if position.x == width - 1 && position.y == height - 1 {
-            println!("result: {}", position.cost);
-            // for direction in position.history {
-            //     println!(
-            //         "{}",
-            //         match direction {
-            //             Direction::North => "N",
-            //             Direction::South => "S",
-            //             Direction::East => "E",
-            //             Direction::West => "W",
-            //         }
-            //     )
-            // }
-            return;
+fn validate_route(
+    layout: &[Vec<u64>],
+    path: &[(usize, usize)],
+    cost: u64,
+    min_run: u8,
+    max_run: u8,
+) -> Vec<String> {
+    let mut problems: Vec<String> = Vec::new();
+    let directions: Vec<Direction> = path
+        .windows(2)
+        .map(|pair| get_direction_between(pair[0], pair[1]))
+        .collect();
+
+    // (direction, length, index of the first move)
+    let mut runs: Vec<(Direction, usize, usize)> = Vec::new();
+    for (index, direction) in directions.iter().enumerate() {
+        match runs.last_mut() {
+            Some((last_direction, length, _)) if last_direction == direction => *length += 1,
+            _ => runs.push((*direction, 1, index)),
+        }
+    }
+    for pair in runs.windows(2) {
+        if pair[1].0 == opposite_direction(pair[0].0) {
+            problems.push(format!("reverses at move {}", pair[1].2));
+        }
+    }
+    for (direction, length, start) in runs {
+        if length < min_run as usize || length > max_run as usize {
+            problems.push(format!(
+                "run of {} {:?} starting at move {} is outside {}..={}",
+                length, direction, start, min_run, max_run
+            ));
         }
+    }
 
-        for (new_x, new_y, direction) in [
-            (position.x as i64 - 1, position.y as i64, Direction::West),
-            (position.x as i64 + 1, position.y as i64, Direction::East),
-            (position.x as i64, position.y as i64 - 1, Direction::North),
-            (position.x as i64, position.y as i64 + 1, Direction::South),
-        ] {
-            if new_x < 0
-                || new_x as usize >= width
-                || new_y < 0
-                || new_y as usize >= height
-                || (position.last_direction == direction && position.last_direction_count == 10)
-                || (position.last_direction != direction && position.last_direction_count < 4)
-                || direction == opposite_direction(position.last_direction)
-            {
-                continue;
-            }
-            // let mut history = position.history.clone();
-            // history.push(direction);
-            positions.insert(Position {
-                x: new_x as usize,
-                y: new_y as usize,
-                last_direction: direction,
-                last_direction_count: if position.last_direction == direction {
-                    position.last_direction_count + 1
-                } else {
-                    1
-                },
-                cost: position.cost + layout[new_y as usize][new_x as usize],
-                // history,
-            });
+    let path_cost: u64 = path.iter().skip(1).map(|(x, y)| layout[*y][*x]).sum();
+    if path_cost != cost {
+        problems.push(format!(
+            "route loses {} heat but the search said {}",
+            path_cost, cost
+        ));
+    }
+    return problems;
+}
+
+pub fn print_route(lines: impl IntoIterator<Item = String>, step: u32) {
+    let layout = parse_layout(lines);
+    let (min_run, max_run) = if step == 1 { (1, 3) } else { (4, 10) };
+    let Some((cost, path)) = find_minimum_heat_loss(&layout, min_run, max_run) else {
+        println!("did not get to end");
+        return;
+    };
+
+    let mut overlay: Vec<Vec<char>> = layout
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|heat_loss| char::from_digit(*heat_loss as u32, 10).unwrap_or('?'))
+                .collect()
+        })
+        .collect();
+    let mut moves = String::new();
+    for pair in path.windows(2) {
+        let arrow = get_arrow(get_direction_between(pair[0], pair[1]));
+        moves.push(arrow);
+        overlay[pair[1].1][pair[1].0] = arrow;
+    }
+
+    for row in overlay {
+        println!("{}", row.into_iter().collect::<String>());
+    }
+    println!();
+    println!("heat loss: {}", cost);
+    println!("moves: {}", moves);
+    println!(
+        "blocks: {}",
+        path.iter()
+            .map(|(x, y)| format!("({}, {})", x, y))
+            .collect::<Vec<String>>()
+            .join(" ")
+    );
+
+    let problems = validate_route(&layout, &path, cost, min_run, max_run);
+    if problems.is_empty() {
+        println!("route is valid for runs of {} to {}", min_run, max_run);
+    }
+    for problem in problems {
+        println!("invalid route: {}", problem);
+    }
+}
+
+pub fn render(lines: Vec<String>, step: u32) -> Canvas {
+    let layout = parse_layout(lines);
+    let (min_run, max_run) = if step == 1 { (1, 3) } else { (4, 10) };
+
+    let mut canvas = Canvas::new(layout[0].len(), layout.len());
+    for (y, row) in layout.iter().enumerate() {
+        for (x, heat_loss) in row.iter().enumerate() {
+            canvas.set(y, x, blend(BACKGROUND, WALL, *heat_loss as f64 / 9.0));
+        }
+    }
+    if let Some((_, path)) = find_minimum_heat_loss(&layout, min_run, max_run) {
+        for (x, y) in path {
+            canvas.set(y, x, ACCENT);
         }
     }
+    return canvas;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ones(width: usize, height: usize) -> Vec<Vec<u64>> {
+        return vec![vec![1; width]; height];
+    }
+
+    #[test]
+    fn test_validate_route_accepts_valid_route() {
+        // arrange
+        let path = [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)];
+
+        // act
+        let result = validate_route(&ones(3, 3), &path, 4, 1, 3);
+
+        // assert
+        assert_eq!(result, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_validate_route_reversal() {
+        // arrange
+        let path = [(0, 0), (1, 0), (0, 0)];
+
+        // act
+        let result = validate_route(&ones(3, 3), &path, 2, 1, 3);
+
+        // assert
+        assert_eq!(result, vec!["reverses at move 1".to_owned()]);
+    }
+
+    #[test]
+    fn test_validate_route_run_too_short() {
+        // arrange
+        let path = [(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (4, 1), (4, 2)];
+
+        // act
+        let result = validate_route(&ones(5, 3), &path, 6, 4, 10);
+
+        // assert
+        assert_eq!(
+            result,
+            vec!["run of 2 South starting at move 4 is outside 4..=10".to_owned()]
+        );
+    }
 
-    println!("did not get to end");
+    #[test]
+    fn test_validate_route_run_too_long() {
+        // arrange
+        let path = [(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)];
+
+        // act
+        let result = validate_route(&ones(5, 1), &path, 4, 1, 3);
+
+        // assert
+        assert_eq!(
+            result,
+            vec!["run of 4 East starting at move 0 is outside 1..=3".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_validate_route_cost_mismatch() {
+        // arrange
+        let path = [(0, 0), (1, 0), (1, 1)];
+
+        // act
+        let result = validate_route(&ones(2, 2), &path, 3, 1, 3);
+
+        // assert
+        assert_eq!(
+            result,
+            vec!["route loses 2 heat but the search said 3".to_owned()]
+        );
+    }
+
+    #[test]
+    fn test_ultra_crucible_needs_a_full_run_at_the_end() {
+        // arrange
+        // the second step 2 example from the puzzle, where the cheapest path that ignores the
+        // minimum run at the end would lose 47 heat
+        let lines = "111111111111
+999999999991
+999999999991
+999999999991
+999999999991"
+            .lines()
+            .map(|line| line.to_owned());
+        let layout = parse_layout(lines);
+
+        // act
+        let result = find_minimum_heat_loss(&layout, 4, 10).map(|(cost, _)| cost);
+
+        // assert
+        assert_eq!(result, Some(71));
+    }
+
+    #[test]
+    fn test_found_routes_are_valid() {
+        // arrange
+        let layout = parse_layout(crate::examples::example_lines(17, 1).unwrap());
+
+        for (min_run, max_run) in [(1, 3), (4, 10)] {
+            // act
+            let (cost, path) = find_minimum_heat_loss(&layout, min_run, max_run).unwrap();
+
+            // assert
+            assert_eq!(
+                validate_route(&layout, &path, cost, min_run, max_run),
+                Vec::<String>::new()
+            );
+        }
+    }
 }
@@ -1,63 +1,440 @@
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
 
 lazy_static! {
-    static ref IMPOSSIBLE_GAME_PATTERN: Regex =
-        Regex::new(r"13 red|14 (?:red|green)|(?:1[5-9]|[2-9]\d|\d{3}) (?:red|green|blue)").unwrap();
-    static ref GAME_NUMBER_PATTERN: Regex = Regex::new(r"\d+").unwrap();
+    static ref GAME_PATTERN: Regex = Regex::new(r"^Game (?<id>\d+): (?<draws>.*)$").unwrap();
+    static ref COLOR_COUNT_PATTERN: Regex = Regex::new(r"^(?<count>\d+) (?<color>\w+)$").unwrap();
 }
 
-pub fn step_1(lines: impl IntoIterator<Item = String>) {
-    let mut sum = 0;
-    for line in lines {
-        if IMPOSSIBLE_GAME_PATTERN.is_match(&line) {
-            continue;
-        }
+// sorted by color so that it prints the same way every time
+pub type Bag = BTreeMap<String, u64>;
+
+pub const DEFAULT_BAG: &str = "red=12,green=13,blue=14";
 
-        let game_number = GAME_NUMBER_PATTERN
-            .find(&line)
-            .expect("could not get game number")
+pub struct Draw {
+    pub counts: Bag,
+}
+
+pub struct Game {
+    pub id: u64,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    pub fn parse(line: &str) -> Game {
+        let captures = GAME_PATTERN.captures(line).expect("could not parse game");
+        let id = captures
+            .name("id")
+            .unwrap()
             .as_str()
-            .parse::<i32>()
+            .parse::<u64>()
             .expect("could not parse game number");
 
-        sum += game_number;
+        let draws = captures
+            .name("draws")
+            .unwrap()
+            .as_str()
+            .split("; ")
+            .map(|draw_str| {
+                let mut counts = Bag::new();
+                for count_str in draw_str.split(", ") {
+                    let capture = COLOR_COUNT_PATTERN
+                        .captures(count_str)
+                        .expect("could not parse color count");
+                    let count = capture
+                        .name("count")
+                        .unwrap()
+                        .as_str()
+                        .parse::<u64>()
+                        .expect("could not parse number");
+                    let color = capture.name("color").unwrap().as_str().to_owned();
+                    *counts.entry(color).or_insert(0) += count;
+                }
+                return Draw { counts };
+            })
+            .collect();
+
+        return Game { id, draws };
     }
 
-    println!("sum: {}", sum);
+    pub fn get_minimal_bag(&self) -> Bag {
+        let mut minimal_bag = Bag::new();
+        for draw in &self.draws {
+            for (color, count) in &draw.counts {
+                let max = minimal_bag.entry(color.clone()).or_insert(0);
+                *max = std::cmp::max(*max, *count);
+            }
+        }
+        return minimal_bag;
+    }
+
+    pub fn is_possible_with(&self, bag: &Bag) -> bool {
+        return self
+            .get_minimal_bag()
+            .iter()
+            .all(|(color, count)| *count <= bag.get(color).copied().unwrap_or(0));
+    }
 }
 
-lazy_static! {
-    static ref COLOR_COUNT_PATTERN: Regex =
-        Regex::new(r"(?<count>\d+) (?<color>red|green|blue)").unwrap();
+pub fn parse_bag(bag_str: &str) -> Bag {
+    let mut bag = Bag::new();
+    for color_str in bag_str.split(",") {
+        let split: Vec<&str> = color_str.trim().split("=").collect();
+        if split.len() != 2 {
+            panic!("expected color=count: {}", color_str);
+        }
+        let count = split[1]
+            .parse::<u64>()
+            .unwrap_or_else(|_| panic!("not a number: {}", split[1]));
+        bag.insert(split[0].to_owned(), count);
+    }
+    return bag;
+}
+
+fn parse_games(lines: impl IntoIterator<Item = String>) -> Vec<Game> {
+    return lines.into_iter().map(|line| Game::parse(&line)).collect();
+}
+
+pub fn solve_step_1_with_bag(lines: impl IntoIterator<Item = String>, bag: &Bag) -> u64 {
+    return parse_games(lines)
+        .iter()
+        .filter(|game| game.is_possible_with(bag))
+        .map(|game| game.id)
+        .sum();
+}
+
+pub fn step_1_with_bag(lines: impl IntoIterator<Item = String>, bag: &Bag) {
+    println!("sum: {}", solve_step_1_with_bag(lines, bag));
+}
+
+pub fn step_1(lines: impl IntoIterator<Item = String>) {
+    step_1_with_bag(lines, &parse_bag(DEFAULT_BAG));
+}
+
+// every color seen in any game is included, so a game that never shows one has a power of 0
+fn get_power(minimal_bag: &Bag, colors: &BTreeSet<String>) -> u64 {
+    return colors
+        .iter()
+        .map(|color| minimal_bag.get(color).copied().unwrap_or(0))
+        .product();
+}
+
+fn get_colors(games: &[Game]) -> BTreeSet<String> {
+    return games
+        .iter()
+        .flat_map(|game| game.get_minimal_bag().into_keys())
+        .collect();
 }
 
 pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    let mut sum = 0;
-    for line in lines {
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
-
-        for capture in COLOR_COUNT_PATTERN.captures_iter(&line) {
-            let count = capture
-                .name("count")
-                .expect("could not get number")
-                .as_str()
-                .parse::<i32>()
-                .expect("could not parse number");
-            let color = capture.name("color").expect("could not get color").as_str();
-
-            match color {
-                "red" => red = std::cmp::max(red, count),
-                "green" => green = std::cmp::max(green, count),
-                "blue" => blue = std::cmp::max(blue, count),
-                _ => panic!("unknown color"),
-            };
+    let games = parse_games(lines);
+    let colors = get_colors(&games);
+    let sum: u64 = games
+        .iter()
+        .map(|game| get_power(&game.get_minimal_bag(), &colors))
+        .sum();
+
+    println!("sum: {}", sum);
+}
+
+fn format_bag(bag: &Bag) -> String {
+    return bag
+        .iter()
+        .map(|(color, count)| format!("{}={}", color, count))
+        .collect::<Vec<String>>()
+        .join(",");
+}
+
+pub fn print_games(lines: impl IntoIterator<Item = String>, bag: &Bag) {
+    let games = parse_games(lines);
+    let colors = get_colors(&games);
+
+    let mut id_sum = 0;
+    let mut power_sum = 0;
+    println!("bag: {}", format_bag(bag));
+    for game in &games {
+        let minimal_bag = game.get_minimal_bag();
+        let is_possible = game.is_possible_with(bag);
+        let power = get_power(&minimal_bag, &colors);
+        if is_possible {
+            id_sum += game.id;
         }
+        power_sum += power;
+        println!(
+            "game {}: {} draws, minimal bag {}, power {}, {}",
+            game.id,
+            game.draws.len(),
+            format_bag(&minimal_bag),
+            power,
+            if is_possible {
+                "possible"
+            } else {
+                "impossible"
+            }
+        );
+    }
+    println!();
+    println!("sum of possible game ids: {}", id_sum);
+    println!("power sum: {}", power_sum);
+}
+
+// larger bags only get less likely once the draws stop ruling them out, so each color is
+// searched up to a multiple of its minimum plus a constant
+const SEARCH_FACTOR: u64 = 3;
+const SEARCH_MARGIN: u64 = 10;
 
-        sum += red * green * blue;
+// half the 95% point of a chi-squared distribution with one degree of freedom
+const CONFIDENCE_LOG_LIKELIHOOD_DROP: f64 = 1.92;
+
+pub struct ColorEstimate {
+    pub color: String,
+    pub minimum: u64,
+    pub most_likely: u64,
+    pub low: u64,
+    pub high: u64,
+    // whether these reached the end of the search, in which case the likelihood is still
+    // rising there and the real values are higher
+    pub most_likely_is_open: bool,
+    pub high_is_open: bool,
+}
+
+fn get_log_factorials(max: u64) -> Vec<f64> {
+    let mut log_factorials: Vec<f64> = vec![0.0];
+    for n in 1..=max {
+        log_factorials.push(log_factorials[n as usize - 1] + (n as f64).ln());
     }
+    return log_factorials;
+}
 
-    println!("sum: {}", sum);
+fn log_choose(log_factorials: &[f64], n: u64, k: u64) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+    return log_factorials[n as usize]
+        - log_factorials[k as usize]
+        - log_factorials[(n - k) as usize];
+}
+
+// returns the best total for every number of cubes, and the count of each color that gets it
+fn combine_tables(tables: &[&Vec<f64>]) -> (Vec<f64>, Vec<Vec<u64>>) {
+    let mut best: Vec<f64> = vec![0.0];
+    let mut counts: Vec<Vec<u64>> = vec![Vec::new()];
+    for table in tables {
+        let mut new_best: Vec<f64> = vec![f64::NEG_INFINITY; best.len() + table.len() - 1];
+        let mut new_counts: Vec<Vec<u64>> = vec![Vec::new(); new_best.len()];
+        for (total, total_value) in best.iter().enumerate() {
+            for (count, value) in table.iter().enumerate() {
+                if total_value + value > new_best[total + count] {
+                    new_best[total + count] = total_value + value;
+                    new_counts[total + count] = counts[total].clone();
+                    new_counts[total + count].push(count as u64);
+                }
+            }
+        }
+        best = new_best;
+        counts = new_counts;
+    }
+    return (best, counts);
+}
+
+// every draw is a handful put back before the next one, so its chance is multivariate
+// hypergeometric. the intervals come from the profile likelihood
+pub fn estimate_bag(game: &Game) -> Vec<ColorEstimate> {
+    let minimal_bag = game.get_minimal_bag();
+    let colors: Vec<&String> = minimal_bag.keys().collect();
+    let caps: Vec<u64> = colors
+        .iter()
+        .map(|color| minimal_bag[*color] * SEARCH_FACTOR + SEARCH_MARGIN)
+        .collect();
+    let log_factorials = get_log_factorials(caps.iter().sum());
+
+    // the log likelihood each color contributes on its own, for every count up to its cap
+    let tables: Vec<Vec<f64>> = colors
+        .iter()
+        .zip(&caps)
+        .map(|(color, cap)| {
+            (0..=*cap)
+                .map(|count| {
+                    game.draws
+                        .iter()
+                        .map(|draw| {
+                            let drawn = draw.counts.get(*color).copied().unwrap_or(0);
+                            log_choose(&log_factorials, count, drawn)
+                        })
+                        .sum()
+                })
+                .collect()
+        })
+        .collect();
+    // and the part that depends only on the total number of cubes
+    let get_total_log_likelihood = |total: usize| -> f64 {
+        return -game
+            .draws
+            .iter()
+            .map(|draw| log_choose(&log_factorials, total as u64, draw.counts.values().sum()))
+            .sum::<f64>();
+    };
+
+    let (best, counts) = combine_tables(&tables.iter().collect::<Vec<&Vec<f64>>>());
+    let (best_total, best_log_likelihood) = best
+        .iter()
+        .enumerate()
+        .map(|(total, value)| (total, value + get_total_log_likelihood(total)))
+        .fold((0, f64::NEG_INFINITY), |a, b| if b.1 > a.1 { b } else { a });
+
+    let mut estimates: Vec<ColorEstimate> = Vec::new();
+    for (color_index, color) in colors.iter().enumerate() {
+        let other_tables: Vec<&Vec<f64>> = tables
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != color_index)
+            .map(|(_, table)| table)
+            .collect();
+        let (other_best, _) = combine_tables(&other_tables);
+
+        let mut plausible_counts: Vec<u64> = Vec::new();
+        for (count, value) in tables[color_index].iter().enumerate() {
+            let profile_log_likelihood = other_best
+                .iter()
+                .enumerate()
+                .map(|(others_total, others_value)| {
+                    value + others_value + get_total_log_likelihood(count + others_total)
+                })
+                .fold(f64::NEG_INFINITY, f64::max);
+            if profile_log_likelihood >= best_log_likelihood - CONFIDENCE_LOG_LIKELIHOOD_DROP {
+                plausible_counts.push(count as u64);
+            }
+        }
+
+        let most_likely = counts[best_total][color_index];
+        let high = *plausible_counts.last().unwrap();
+        estimates.push(ColorEstimate {
+            color: color.to_string(),
+            minimum: minimal_bag[*color],
+            most_likely,
+            low: plausible_counts[0],
+            high,
+            most_likely_is_open: most_likely == caps[color_index],
+            high_is_open: high == caps[color_index],
+        });
+    }
+    return estimates;
+}
+
+pub fn print_bag_estimates(lines: impl IntoIterator<Item = String>) {
+    for game in parse_games(lines) {
+        println!("game {} ({} draws):", game.id, game.draws.len());
+        for estimate in estimate_bag(&game) {
+            let or_more = |is_open: bool| if is_open { " or more" } else { "" };
+            println!(
+                "  {:<8} minimum {:>3}, most likely {:>3}{}, 95% interval {}..={}{}",
+                estimate.color,
+                estimate.minimum,
+                estimate.most_likely,
+                or_more(estimate.most_likely_is_open),
+                estimate.low,
+                estimate.high,
+                or_more(estimate.high_is_open)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bag_with_extra_color() {
+        // arrange
+        let lines = vec![
+            "Game 1: 3 blue, 4 red; 2 yellow".to_owned(),
+            "Game 2: 1 blue, 4 yellow".to_owned(),
+            "Game 3: 12 red, 13 green, 14 blue".to_owned(),
+        ];
+        let bag = parse_bag("red=12,green=13,blue=14,yellow=3");
+
+        // act
+        let result = solve_step_1_with_bag(lines, &bag);
+
+        // assert
+        assert_eq!(result, 1 + 3);
+    }
+
+    #[test]
+    fn test_single_draw_is_most_likely_the_minimal_bag() {
+        // arrange
+        let game = Game::parse("Game 1: 3 blue, 4 red");
+
+        // act
+        let result: Vec<(String, u64, u64)> = estimate_bag(&game)
+            .into_iter()
+            .map(|estimate| (estimate.color, estimate.most_likely, estimate.low))
+            .collect();
+
+        // assert
+        assert_eq!(
+            result,
+            vec![("blue".to_owned(), 3, 3), ("red".to_owned(), 4, 4)]
+        );
+    }
+
+    fn summarize(estimates: Vec<ColorEstimate>) -> Vec<(String, u64, bool, u64, u64, bool)> {
+        return estimates
+            .into_iter()
+            .map(|estimate| {
+                (
+                    estimate.color,
+                    estimate.most_likely,
+                    estimate.most_likely_is_open,
+                    estimate.low,
+                    estimate.high,
+                    estimate.high_is_open,
+                )
+            })
+            .collect();
+    }
+
+    #[test]
+    fn test_repeated_draws_close_the_interval() {
+        // arrange
+        // with one of each, every draw is certain. with n of each, each draw has a chance of
+        // n / (2n - 1), so over three draws the log likelihood drops by 1.908 at 9 of each and
+        // by 1.926 at 10, just past the cutoff
+        let game = Game::parse("Game 1: 1 red, 1 blue; 1 blue, 1 red; 1 red, 1 blue");
+
+        // act
+        let result = summarize(estimate_bag(&game));
+
+        // assert
+        assert_eq!(
+            result,
+            vec![
+                ("blue".to_owned(), 1, false, 1, 9, false),
+                ("red".to_owned(), 1, false, 1, 9, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_most_likely_bag_above_the_minimum() {
+        // arrange
+        // the chance of the draws is C(r, 2) / C(r + b, 2) * r * b / C(r + b, 2), which is 2/9
+        // for the minimal bag, 1/4 for 3 red and 1 blue, and 6/25 for 4 red and 1 blue. larger
+        // bags with the same mix stay close to 1/4, so the interval runs off the end of the search
+        let game = Game::parse("Game 1: 2 red; 1 red, 1 blue");
+
+        // act
+        let result = summarize(estimate_bag(&game));
+
+        // assert
+        assert_eq!(
+            result,
+            vec![
+                ("blue".to_owned(), 1, false, 1, 13, true),
+                ("red".to_owned(), 3, false, 2, 16, true),
+            ]
+        );
+    }
 }
@@ -1,130 +1,979 @@
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::ops::Range;
 
 lazy_static! {
-    static ref SYMBOL_PATTERN: Regex = Regex::new(r"[^\.\d]").unwrap();
     static ref NUMBER_PATTERN: Regex = Regex::new(r"\d+").unwrap();
-    static ref GEAR_PATTERN: Regex = Regex::new(r"\*").unwrap();
 }
 
-fn get_symbol_indices(line: &str) -> HashSet<usize> {
-    return SYMBOL_PATTERN.find_iter(line).map(|m| m.start()).collect();
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GearCredit {
+    // a number only counts towards the first gear it touches, from the top left
+    First,
+    All,
 }
 
-pub fn step_1(lines: impl IntoIterator<Item = String>) {
-    let mut lines_iterator = lines.into_iter();
-
-    let mut current_line_option = lines_iterator.next();
-    let mut next_line_option = lines_iterator.next();
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GearScore {
+    Product,
+    Sum,
+}
 
-    let mut previous_symbol_indices: HashSet<usize> = HashSet::new();
-    let mut current_symbol_indices: HashSet<usize> = match &current_line_option {
-        Some(line) => get_symbol_indices(&line),
-        None => HashSet::new(),
-    };
-    let mut next_symbol_indices: HashSet<usize> = match &next_line_option {
-        Some(line) => get_symbol_indices(&line),
-        None => HashSet::new(),
-    };
+#[derive(Clone, PartialEq, Debug)]
+pub struct Rules {
+    pub radius: usize,
+    pub orthogonal: bool,
+    // None means anything other than `.` and digits
+    pub symbols: Option<Vec<u8>>,
+    pub gear_symbol: u8,
+    pub gear_part_count: usize,
+    pub gear_score: GearScore,
+    pub gear_credit: GearCredit,
+}
 
-    let mut sum = 0;
+impl Default for Rules {
+    fn default() -> Rules {
+        return Rules {
+            radius: 1,
+            orthogonal: false,
+            symbols: None,
+            gear_symbol: b'*',
+            gear_part_count: 2,
+            gear_score: GearScore::Product,
+            gear_credit: GearCredit::First,
+        };
+    }
+}
 
-    while let Some(current_line) = current_line_option {
-        for m in NUMBER_PATTERN.find_iter(&current_line) {
-            let range_start = if m.start() == 0 { 0 } else { m.start() - 1 };
-            for i in range_start..(m.end() + 1) {
-                if previous_symbol_indices.contains(&i)
-                    || current_symbol_indices.contains(&i)
-                    || next_symbol_indices.contains(&i)
-                {
-                    sum += m.as_str().parse::<i32>().unwrap();
-                    break;
+impl Rules {
+    // the rules are split on commas, so a comma can't be a symbol
+    pub fn parse(rules_str: &str) -> Result<Rules, String> {
+        let mut rules = Rules::default();
+        for rule in rules_str.split(",") {
+            let parse_count = |value: &str| {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| format!("{} is not a count in {}", value, rule))
+            };
+            match rule.split_once("=") {
+                None if rule == "orthogonal" => rules.orthogonal = true,
+                None if rule.is_empty() => return Err(format!("empty rule in {:?}", rules_str)),
+                Some(("radius", value)) => rules.radius = parse_count(value)?,
+                Some(("symbols", value)) => rules.symbols = Some(value.as_bytes().to_vec()),
+                Some(("gear", value)) if value.len() == 1 => {
+                    rules.gear_symbol = value.as_bytes()[0]
                 }
+                Some(("gear", _)) => return Err(format!("gear has to be one byte in {}", rule)),
+                Some(("gear-parts", value)) => rules.gear_part_count = parse_count(value)?,
+                Some(("gear-score", "product")) => rules.gear_score = GearScore::Product,
+                Some(("gear-score", "sum")) => rules.gear_score = GearScore::Sum,
+                Some(("gear-credit", "first")) => rules.gear_credit = GearCredit::First,
+                Some(("gear-credit", "all")) => rules.gear_credit = GearCredit::All,
+                _ => return Err(format!("unknown rule: {}", rule)),
             }
         }
+        return Ok(rules);
+    }
 
-        current_line_option = next_line_option;
-        next_line_option = lines_iterator.next();
-
-        previous_symbol_indices = current_symbol_indices;
-        current_symbol_indices = next_symbol_indices;
-        next_symbol_indices = match &next_line_option {
-            Some(line) => get_symbol_indices(&line),
-            None => HashSet::new(),
+    fn get_gear_score(&self, parts: &[i64]) -> Option<i64> {
+        if parts.len() != self.gear_part_count {
+            return None;
         }
+        return Some(match self.gear_score {
+            GearScore::Product => parts.iter().product(),
+            GearScore::Sum => parts.iter().sum(),
+        });
+    }
+
+    fn is_symbol(&self, byte: u8) -> bool {
+        return match &self.symbols {
+            Some(symbols) => symbols.contains(&byte),
+            None => byte != b'.' && !byte.is_ascii_digit(),
+        };
     }
 
-    println!("sum: {}", sum);
+    fn is_adjacent(
+        &self,
+        number_line_index: usize,
+        start: usize,
+        end: usize,
+        line_index: usize,
+        byte_index: usize,
+    ) -> bool {
+        let line_distance = number_line_index.abs_diff(line_index);
+        let byte_distance = if byte_index < start {
+            start - byte_index
+        } else if byte_index >= end {
+            byte_index - end + 1
+        } else {
+            0
+        };
+        if self.orthogonal {
+            return line_distance + byte_distance <= self.radius;
+        }
+        return std::cmp::max(line_distance, byte_distance) <= self.radius;
+    }
 }
 
-struct Gear {
-    index: usize,
-    product: i32,
-    count: i32,
+fn get_symbol_indices(line: &str, rules: &Rules) -> Vec<usize> {
+    return line
+        .bytes()
+        .enumerate()
+        .filter(|(_, byte)| rules.is_symbol(*byte) || *byte == rules.gear_symbol)
+        .map(|(byte_index, _)| byte_index)
+        .collect();
 }
 
-fn get_gears(line: &str) -> Vec<Gear> {
-    return GEAR_PATTERN
+fn get_numbers(line: &str) -> Vec<(usize, usize, i64)> {
+    return NUMBER_PATTERN
         .find_iter(line)
-        .map(|m| Gear {
-            index: m.start(),
-            product: 1,
-            count: 0,
-        })
+        .map(|m| (m.start(), m.end(), m.as_str().parse::<i64>().unwrap()))
         .collect();
 }
 
-pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    let mut lines_iterator = lines.into_iter();
+#[derive(Clone, PartialEq, Debug)]
+pub struct Number {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+    pub value: i64,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Symbol {
+    pub row: usize,
+    pub column: usize,
+    pub byte: u8,
+}
+
+pub struct Gear {
+    pub symbol_id: usize,
+    pub parts: Vec<i64>,
+}
+
+impl Gear {
+    pub fn get_score(&self, rules: &Rules) -> Option<i64> {
+        return rules.get_gear_score(&self.parts);
+    }
+}
+
+struct WindowLine {
+    line_index: usize,
+    line: String,
+    symbol_ids: Range<usize>,
+}
+
+pub struct Schematic {
+    pub rules: Rules,
+    pub numbers: Vec<Number>,
+    // sorted from the top left, and so are the numbers
+    pub symbols: Vec<Symbol>,
+    // (number id, symbol id), sorted by number and then by symbol
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Schematic {
+    // only holds the lines within `radius` of the one whose numbers are being looked at
+    pub fn build(lines: impl IntoIterator<Item = String>, rules: &Rules) -> Schematic {
+        let mut schematic = Schematic {
+            rules: rules.clone(),
+            numbers: Vec::new(),
+            symbols: Vec::new(),
+            edges: Vec::new(),
+        };
+        let mut lines_iterator = lines.into_iter().enumerate();
+        let mut window: VecDeque<WindowLine> = VecDeque::new();
+        let mut reached_end = false;
+
+        let mut center = 0;
+        loop {
+            while !reached_end
+                && window
+                    .back()
+                    .is_none_or(|window_line| window_line.line_index < center + rules.radius)
+            {
+                let Some((line_index, line)) = lines_iterator.next() else {
+                    reached_end = true;
+                    break;
+                };
+                let first_symbol_id = schematic.symbols.len();
+                for column in get_symbol_indices(&line, rules) {
+                    schematic.symbols.push(Symbol {
+                        row: line_index,
+                        column,
+                        byte: line.as_bytes()[column],
+                    });
+                }
+                window.push_back(WindowLine {
+                    line_index,
+                    line,
+                    symbol_ids: first_symbol_id..schematic.symbols.len(),
+                });
+            }
+            while window
+                .front()
+                .is_some_and(|window_line| window_line.line_index + rules.radius < center)
+            {
+                window.pop_front();
+            }
+
+            let Some(center_line) = window
+                .iter()
+                .find(|window_line| window_line.line_index == center)
+            else {
+                break;
+            };
+            for (start, end, value) in get_numbers(&center_line.line) {
+                let number_id = schematic.numbers.len();
+                schematic.numbers.push(Number {
+                    row: center,
+                    start,
+                    end,
+                    value,
+                });
+                for symbol_id in window
+                    .iter()
+                    .flat_map(|window_line| window_line.symbol_ids.clone())
+                {
+                    let symbol = &schematic.symbols[symbol_id];
+                    if rules.is_adjacent(center, start, end, symbol.row, symbol.column) {
+                        schematic.edges.push((number_id, symbol_id));
+                    }
+                }
+            }
 
-    let mut current_line_option = lines_iterator.next();
-    let mut next_line_option = lines_iterator.next();
+            center += 1;
+        }
+
+        return schematic;
+    }
+
+    pub fn get_adjacent_symbols(&self, number_id: usize) -> impl Iterator<Item = usize> + '_ {
+        let start = self.edges.partition_point(|(id, _)| *id < number_id);
+        return self.edges[start..]
+            .iter()
+            .take_while(move |(id, _)| *id == number_id)
+            .map(|(_, symbol_id)| *symbol_id);
+    }
+
+    pub fn is_part_number(&self, number_id: usize) -> bool {
+        return self
+            .get_adjacent_symbols(number_id)
+            .any(|symbol_id| self.rules.is_symbol(self.symbols[symbol_id].byte));
+    }
+
+    // with `GearCredit::First`, a number between two gears only counts for one
+    pub fn get_credited_gears(&self, number_id: usize) -> Vec<usize> {
+        let gear_ids = self
+            .get_adjacent_symbols(number_id)
+            .filter(|symbol_id| self.symbols[*symbol_id].byte == self.rules.gear_symbol);
+        return match self.rules.gear_credit {
+            GearCredit::First => gear_ids.take(1).collect(),
+            GearCredit::All => gear_ids.collect(),
+        };
+    }
+
+    pub fn get_gears(&self) -> Vec<Gear> {
+        let mut parts: Vec<Vec<i64>> = vec![Vec::new(); self.symbols.len()];
+        for (number_id, number) in self.numbers.iter().enumerate() {
+            for symbol_id in self.get_credited_gears(number_id) {
+                parts[symbol_id].push(number.value);
+            }
+        }
+        return parts
+            .into_iter()
+            .enumerate()
+            .filter(|(symbol_id, _)| self.symbols[*symbol_id].byte == self.rules.gear_symbol)
+            .map(|(symbol_id, parts)| Gear { symbol_id, parts })
+            .collect();
+    }
+
+    pub fn get_part_number_sum(&self) -> i64 {
+        return (0..self.numbers.len())
+            .filter(|number_id| self.is_part_number(*number_id))
+            .map(|number_id| self.numbers[number_id].value)
+            .sum();
+    }
+
+    pub fn get_gear_score_sum(&self) -> i64 {
+        return self
+            .get_gears()
+            .iter()
+            .filter_map(|gear| gear.get_score(&self.rules))
+            .sum();
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut gear_scores: Vec<Option<i64>> = vec![None; self.symbols.len()];
+        for gear in self.get_gears() {
+            gear_scores[gear.symbol_id] = gear.get_score(&self.rules);
+        }
+        let format_entries = |entries: Vec<String>| -> String {
+            if entries.is_empty() {
+                return "[]".to_owned();
+            }
+            return format!("[\n    {}\n  ]", entries.join(",\n    "));
+        };
+
+        let numbers = self
+            .numbers
+            .iter()
+            .enumerate()
+            .map(|(number_id, number)| {
+                let gear_ids: Vec<String> = self
+                    .get_credited_gears(number_id)
+                    .iter()
+                    .map(|symbol_id| symbol_id.to_string())
+                    .collect();
+                format!(
+                    "{{\"id\": {}, \"value\": {}, \"row\": {}, \"start\": {}, \"end\": {}, \"part\": {}, \"gears\": [{}]}}",
+                    number_id,
+                    number.value,
+                    number.row,
+                    number.start,
+                    number.end,
+                    self.is_part_number(number_id),
+                    gear_ids.join(", ")
+                )
+            })
+            .collect();
+        let symbols = self
+            .symbols
+            .iter()
+            .enumerate()
+            .map(|(symbol_id, symbol)| {
+                format!(
+                    "{{\"id\": {}, \"symbol\": \"{}\", \"row\": {}, \"column\": {}, \"gear_score\": {}}}",
+                    symbol_id,
+                    escape_json(symbol.byte),
+                    symbol.row,
+                    symbol.column,
+                    gear_scores[symbol_id].map_or("null".to_owned(), |score| score.to_string())
+                )
+            })
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .map(|(number_id, symbol_id)| {
+                format!("{{\"number\": {}, \"symbol\": {}}}", number_id, symbol_id)
+            })
+            .collect();
 
-    let mut previous_gears: Vec<Gear> = Vec::new();
-    let mut current_gears: Vec<Gear> = match &current_line_option {
-        Some(line) => get_gears(&line),
-        None => Vec::new(),
+        return format!(
+            "{{\n  \"numbers\": {},\n  \"symbols\": {},\n  \"edges\": {},\n  \"part_number_sum\": {},\n  \"gear_score_sum\": {}\n}}\n",
+            format_entries(numbers),
+            format_entries(symbols),
+            format_entries(edges),
+            self.get_part_number_sum(),
+            self.get_gear_score_sum()
+        );
+    }
+}
+
+fn escape_json(byte: u8) -> String {
+    return match byte {
+        b'"' => "\\\"".to_owned(),
+        b'\\' => "\\\\".to_owned(),
+        // lines are read as UTF-8, but symbols are single bytes, so anything outside ASCII is
+        // written as its byte value
+        0x20..=0x7e => (byte as char).to_string(),
+        _ => format!("\\u{:04x}", byte),
     };
-    let mut next_gears: Vec<Gear> = match &next_line_option {
-        Some(line) => get_gears(&line),
-        None => Vec::new(),
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Edit {
+    pub row: usize,
+    pub column: usize,
+    pub byte: u8,
+}
+
+struct Row {
+    numbers: Vec<(usize, usize, i64)>,
+    symbol_indices: Vec<usize>,
+    part_number_sum: i64,
+    gear_score_sum: i64,
+}
+
+// keeps the sums up to date by only looking again at the rows near each edit
+pub struct EditableSchematic {
+    rules: Rules,
+    lines: Vec<String>,
+    rows: Vec<Row>,
+    part_number_sum: i64,
+    gear_score_sum: i64,
+}
+
+impl EditableSchematic {
+    pub fn new(lines: impl IntoIterator<Item = String>, rules: &Rules) -> EditableSchematic {
+        let lines: Vec<String> = lines.into_iter().collect();
+        let rows = lines
+            .iter()
+            .map(|line| Row {
+                numbers: get_numbers(line),
+                symbol_indices: get_symbol_indices(line, rules),
+                part_number_sum: 0,
+                gear_score_sum: 0,
+            })
+            .collect();
+        let mut schematic = EditableSchematic {
+            rules: rules.clone(),
+            lines,
+            rows,
+            part_number_sum: 0,
+            gear_score_sum: 0,
+        };
+        let all_rows: Vec<usize> = (0..schematic.rows.len()).collect();
+        schematic.update_sums(&all_rows, &all_rows);
+        return schematic;
+    }
+
+    pub fn get_lines(&self) -> &[String] {
+        return &self.lines;
+    }
+
+    pub fn get_part_number_sum(&self) -> i64 {
+        return self.part_number_sum;
+    }
+
+    pub fn get_gear_score_sum(&self) -> i64 {
+        return self.gear_score_sum;
+    }
+
+    // returns how many rows had to be looked at again. a cell past the end of its line pads the
+    // line with `.`
+    pub fn apply_edits(&mut self, edits: &[Edit]) -> usize {
+        let mut edited_rows: Vec<usize> = Vec::new();
+        for edit in edits {
+            let line = self.lines.get_mut(edit.row).expect("no such row");
+            while line.len() <= edit.column {
+                line.push('.');
+            }
+            if !edit.byte.is_ascii()
+                || !line.is_char_boundary(edit.column)
+                || !line.is_char_boundary(edit.column + 1)
+            {
+                panic!("can only edit ASCII cells");
+            }
+            line.replace_range(
+                edit.column..(edit.column + 1),
+                &(edit.byte as char).to_string(),
+            );
+            edited_rows.push(edit.row);
+        }
+        edited_rows.sort();
+        edited_rows.dedup();
+
+        for row in &edited_rows {
+            self.rows[*row].numbers = get_numbers(&self.lines[*row]);
+            self.rows[*row].symbol_indices = get_symbol_indices(&self.lines[*row], &self.rules);
+        }
+
+        // a number can only change whether it's a part if it's within reach of an edit, and a
+        // gear can only change if one of those numbers is within reach of it
+        let part_rows = self.get_rows_within(&edited_rows, self.rules.radius);
+        let gear_rows = self.get_rows_within(&edited_rows, 2 * self.rules.radius);
+        self.update_sums(&part_rows, &gear_rows);
+        return gear_rows.len();
+    }
+
+    fn get_rows_within(&self, rows: &[usize], distance: usize) -> Vec<usize> {
+        let mut nearby_rows: Vec<usize> = rows
+            .iter()
+            .flat_map(|row| {
+                row.saturating_sub(distance)..std::cmp::min(row + distance + 1, self.rows.len())
+            })
+            .collect();
+        nearby_rows.sort();
+        nearby_rows.dedup();
+        return nearby_rows;
+    }
+
+    fn update_sums(&mut self, part_rows: &[usize], gear_rows: &[usize]) {
+        for row in part_rows {
+            let part_number_sum = self.rows[*row]
+                .numbers
+                .iter()
+                .filter(|(start, end, _)| self.is_part_number(*row, *start, *end))
+                .map(|(_, _, value)| value)
+                .sum();
+            self.part_number_sum += part_number_sum - self.rows[*row].part_number_sum;
+            self.rows[*row].part_number_sum = part_number_sum;
+        }
+        for row in gear_rows {
+            let gear_score_sum = self.rows[*row]
+                .symbol_indices
+                .iter()
+                .filter(|column| self.lines[*row].as_bytes()[**column] == self.rules.gear_symbol)
+                .filter_map(|column| {
+                    self.rules
+                        .get_gear_score(&self.get_gear_parts(*row, *column))
+                })
+                .sum();
+            self.gear_score_sum += gear_score_sum - self.rows[*row].gear_score_sum;
+            self.rows[*row].gear_score_sum = gear_score_sum;
+        }
+    }
+
+    fn get_adjacent_symbols(
+        &self,
+        row: usize,
+        start: usize,
+        end: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        return self
+            .get_rows_within(&[row], self.rules.radius)
+            .into_iter()
+            .flat_map(move |other_row| {
+                self.rows[other_row]
+                    .symbol_indices
+                    .iter()
+                    .filter(move |column| {
+                        self.rules.is_adjacent(row, start, end, other_row, **column)
+                    })
+                    .map(move |column| (other_row, *column))
+            });
+    }
+
+    fn is_part_number(&self, row: usize, start: usize, end: usize) -> bool {
+        return self
+            .get_adjacent_symbols(row, start, end)
+            .any(|(other_row, column)| {
+                self.rules
+                    .is_symbol(self.lines[other_row].as_bytes()[column])
+            });
+    }
+
+    fn get_gear_parts(&self, row: usize, column: usize) -> Vec<i64> {
+        let mut parts: Vec<i64> = Vec::new();
+        for number_row in self.get_rows_within(&[row], self.rules.radius) {
+            for (start, end, value) in &self.rows[number_row].numbers {
+                let mut gears = self.get_adjacent_symbols(number_row, *start, *end).filter(
+                    |(other_row, other_column)| {
+                        self.lines[*other_row].as_bytes()[*other_column] == self.rules.gear_symbol
+                    },
+                );
+                let is_credited = match self.rules.gear_credit {
+                    GearCredit::First => gears.next() == Some((row, column)),
+                    GearCredit::All => gears.any(|gear| gear == (row, column)),
+                };
+                if is_credited {
+                    parts.push(*value);
+                }
+            }
+        }
+        return parts;
+    }
+}
+
+pub fn solve_step_1_with_rules(lines: impl IntoIterator<Item = String>, rules: &Rules) -> i64 {
+    return Schematic::build(lines, rules).get_part_number_sum();
+}
+
+pub fn step_1_with_rules(lines: impl IntoIterator<Item = String>, rules: &Rules) {
+    println!("sum: {}", solve_step_1_with_rules(lines, rules));
+}
+
+pub fn step_1(lines: impl IntoIterator<Item = String>) {
+    step_1_with_rules(lines, &Rules::default());
+}
+
+pub fn solve_step_2_with_rules(lines: impl IntoIterator<Item = String>, rules: &Rules) -> i64 {
+    return Schematic::build(lines, rules).get_gear_score_sum();
+}
+
+pub fn step_2_with_rules(lines: impl IntoIterator<Item = String>, rules: &Rules) {
+    println!("sum: {}", solve_step_2_with_rules(lines, rules));
+}
+
+pub fn step_2(lines: impl IntoIterator<Item = String>) {
+    step_2_with_rules(lines, &Rules::default());
+}
+
+fn escape_html(byte: u8) -> String {
+    return match byte {
+        b'&' => "&amp;".to_owned(),
+        b'<' => "&lt;".to_owned(),
+        b'>' => "&gt;".to_owned(),
+        b'"' => "&quot;".to_owned(),
+        _ => (byte as char).to_string(),
     };
+}
+
+const HTML_HEADER: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Engine schematic</title>
+<style>
+body { background: #1e1e24; color: #8a8a94; font-family: monospace; }
+pre { font-size: 16px; line-height: 1.2; }
+.part { color: #f0c040; font-weight: bold; }
+.other { color: #55555e; }
+.symbol { color: #e0e0e8; }
+.gear { color: #1e1e24; background: #d24632; font-weight: bold; cursor: help; }
+</style>
+</head>
+<body>
+";
 
-    let mut sum = 0;
+pub fn to_html(lines: impl IntoIterator<Item = String>, rules: &Rules) -> String {
+    let lines: Vec<String> = lines.into_iter().collect();
+    let schematic = Schematic::build(lines.clone(), rules);
+    let mut gear_scores: Vec<Option<i64>> = vec![None; schematic.symbols.len()];
+    for gear in schematic.get_gears() {
+        gear_scores[gear.symbol_id] = gear.get_score(rules);
+    }
 
-    while let Some(current_line) = current_line_option {
-        for m in NUMBER_PATTERN.find_iter(&current_line) {
-            let range_start = if m.start() == 0 { 0 } else { m.start() - 1 };
-            for gear in previous_gears
-                .iter_mut()
-                .chain(current_gears.iter_mut())
-                .chain(next_gears.iter_mut())
+    // both lists are sorted from the top left, so they can be walked alongside the cells
+    let mut numbers = schematic.numbers.iter().enumerate().peekable();
+    let mut symbols = schematic.symbols.iter().enumerate().peekable();
+    let mut html = HTML_HEADER.to_owned();
+    html += "<pre>\n";
+    for (line_index, line) in lines.iter().enumerate() {
+        let bytes = line.as_bytes();
+        let mut byte_index = 0;
+        while byte_index < bytes.len() {
+            if let Some((number_id, number)) = numbers
+                .next_if(|(_, number)| number.row == line_index && number.start == byte_index)
             {
-                if gear.index >= range_start && gear.index <= m.end() {
-                    gear.product *= m.as_str().parse::<i32>().unwrap();
-                    gear.count += 1;
-                    break;
+                let class = if schematic.is_part_number(number_id) {
+                    "part"
+                } else {
+                    "other"
+                };
+                html += &format!(
+                    "<span class=\"{}\">{}</span>",
+                    class,
+                    &line[number.start..number.end]
+                );
+                byte_index = number.end;
+                continue;
+            }
+
+            let byte = bytes[byte_index];
+            let symbol = symbols
+                .next_if(|(_, symbol)| symbol.row == line_index && symbol.column == byte_index);
+            match symbol.map(|(symbol_id, _)| gear_scores[symbol_id]) {
+                Some(Some(score)) => {
+                    html += &format!(
+                        "<span class=\"gear\" title=\"gear score {}\">{}</span>",
+                        score,
+                        escape_html(byte)
+                    );
+                }
+                Some(None) => {
+                    html += &format!("<span class=\"symbol\">{}</span>", escape_html(byte));
                 }
+                // anything that isn't a symbol under these rules is drawn as it is, unstyled
+                None => html += &escape_html(byte),
             }
+            byte_index += 1;
         }
+        html += "\n";
+    }
+    html += "</pre>\n";
+    html += &format!(
+        "<p>part number sum: {}<br>gear score sum: {}</p>\n",
+        schematic.get_part_number_sum(),
+        schematic.get_gear_score_sum()
+    );
+    html += "</body>\n</html>\n";
+    return html;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
 
-        for gear in previous_gears {
-            if gear.count == 2 {
-                sum += gear.product;
+    fn brute_force(lines: &[String], rules: &Rules) -> (i64, i64) {
+        let mut part_sum = 0;
+        let mut gear_parts: Vec<((usize, usize), Vec<i64>)> = Vec::new();
+        for (line_index, line) in lines.iter().enumerate() {
+            for (byte_index, byte) in line.bytes().enumerate() {
+                if byte == rules.gear_symbol {
+                    gear_parts.push(((line_index, byte_index), Vec::new()));
+                }
+            }
+        }
+        for (line_index, line) in lines.iter().enumerate() {
+            for m in NUMBER_PATTERN.find_iter(line) {
+                let value = m.as_str().parse::<i64>().unwrap();
+                let touches = |other_line_index: usize, byte_index: usize| {
+                    rules.is_adjacent(line_index, m.start(), m.end(), other_line_index, byte_index)
+                };
+                if lines
+                    .iter()
+                    .enumerate()
+                    .any(|(other_line_index, other_line)| {
+                        other_line.bytes().enumerate().any(|(byte_index, byte)| {
+                            rules.is_symbol(byte) && touches(other_line_index, byte_index)
+                        })
+                    })
+                {
+                    part_sum += value;
+                }
+                let mut touching_gears = gear_parts
+                    .iter_mut()
+                    .filter(|((gear_line_index, index), _)| touches(*gear_line_index, *index));
+                if rules.gear_credit == GearCredit::First {
+                    if let Some((_, parts)) = touching_gears.next() {
+                        parts.push(value);
+                    }
+                } else {
+                    for (_, parts) in touching_gears {
+                        parts.push(value);
+                    }
+                }
             }
         }
 
-        current_line_option = next_line_option;
-        next_line_option = lines_iterator.next();
+        let gear_sum = gear_parts
+            .into_iter()
+            .filter_map(|(_, parts)| {
+                Gear {
+                    symbol_id: 0,
+                    parts,
+                }
+                .get_score(rules)
+            })
+            .sum();
+        return (part_sum, gear_sum);
+    }
+
+    #[test]
+    fn test_default_rules_match_example() {
+        // arrange
+        let lines = crate::examples::example_lines(3, 1).unwrap();
+
+        // act
+        let result = (
+            solve_step_1_with_rules(lines.clone(), &Rules::default()),
+            solve_step_2_with_rules(lines, &Rules::default()),
+        );
+
+        // assert
+        assert_eq!(result, (4361, 467835));
+    }
 
-        previous_gears = current_gears;
-        current_gears = next_gears;
-        next_gears = match &next_line_option {
-            Some(line) => get_gears(&line),
-            None => Vec::new(),
+    #[test]
+    fn test_to_html_keeps_non_symbols() {
+        // arrange
+        let lines = crate::examples::example_lines(3, 1).unwrap();
+        let rules = Rules::parse("symbols=$,gear=$").unwrap();
+
+        // act
+        let result = to_html(lines, &rules);
+
+        // assert
+        assert!(result.contains("\n......#...\n"));
+        assert!(result.contains("...<span class=\"symbol\">$</span>.*....\n"));
+        assert!(result.contains("part number sum: 664<br>"));
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        // arrange
+        let rules_str =
+            "radius=2,orthogonal,symbols=*#,gear=#,gear-parts=3,gear-score=sum,gear-credit=all";
+
+        // act
+        let result = Rules::parse(rules_str);
+
+        // assert
+        assert_eq!(
+            result,
+            Ok(Rules {
+                radius: 2,
+                orthogonal: true,
+                symbols: Some(b"*#".to_vec()),
+                gear_symbol: b'#',
+                gear_part_count: 3,
+                gear_score: GearScore::Sum,
+                gear_credit: GearCredit::All,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_bad_rules() {
+        // arrange
+        let rules_strs = [
+            "",
+            "radius=2,",
+            "radius=-1",
+            "gear=**",
+            "gear-score=max",
+            "diagonal",
+        ];
+
+        // act
+        let result: Vec<Result<Rules, String>> = rules_strs
+            .iter()
+            .map(|rules_str| Rules::parse(rules_str))
+            .collect();
+
+        // assert
+        assert_eq!(
+            result,
+            vec![
+                Err("empty rule in \"\"".to_owned()),
+                Err("empty rule in \"radius=2,\"".to_owned()),
+                Err("-1 is not a count in radius=-1".to_owned()),
+                Err("gear has to be one byte in gear=**".to_owned()),
+                Err("unknown rule: gear-score=max".to_owned()),
+                Err("unknown rule: diagonal".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_window_matches_brute_force() {
+        // arrange
+        let mut rng = Rng::new(3);
+        for case in 0..200 {
+            let lines = crate::generate::generate(3, case, 12);
+            let rules = Rules {
+                radius: rng.range(0, 4) as usize,
+                orthogonal: rng.chance(0.5),
+                symbols: if rng.chance(0.5) {
+                    None
+                } else {
+                    Some(b"*#".to_vec())
+                },
+                gear_symbol: rng.pick(b"*#+"),
+                gear_part_count: rng.range(1, 4) as usize,
+                gear_score: rng.pick(&[GearScore::Product, GearScore::Sum]),
+                gear_credit: rng.pick(&[GearCredit::First, GearCredit::All]),
+            };
+
+            // act
+            let result = (
+                solve_step_1_with_rules(lines.clone(), &rules),
+                solve_step_2_with_rules(lines.clone(), &rules),
+            );
+
+            // assert
+            assert_eq!(result, brute_force(&lines, &rules), "{:?}", rules);
+        }
+    }
+
+    #[test]
+    fn test_number_between_gears_is_credited_once() {
+        // arrange
+        let lines = vec!["*.*3".to_owned(), ".5..".to_owned()];
+        let first = Rules::default();
+        let all = Rules {
+            gear_credit: GearCredit::All,
+            ..Rules::default()
         };
+
+        // act
+        let first_schematic = Schematic::build(lines.clone(), &first);
+        let all_schematic = Schematic::build(lines, &all);
+
+        // assert
+        assert_eq!(first_schematic.edges, vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(first_schematic.get_credited_gears(1), vec![0]);
+        assert_eq!(all_schematic.get_credited_gears(1), vec![0, 1]);
+        assert_eq!(first_schematic.get_gear_score_sum(), 0);
+        assert_eq!(all_schematic.get_gear_score_sum(), 15);
+    }
+
+    #[test]
+    fn test_to_json_snapshot() {
+        // arrange
+        let lines = vec!["12*".to_owned(), "..#".to_owned(), "7.3".to_owned()];
+
+        // act
+        let result = Schematic::build(lines, &Rules::default()).to_json();
+
+        // assert
+        assert_eq!(
+            result,
+            r##"{
+  "numbers": [
+    {"id": 0, "value": 12, "row": 0, "start": 0, "end": 2, "part": true, "gears": [0]},
+    {"id": 1, "value": 7, "row": 2, "start": 0, "end": 1, "part": false, "gears": []},
+    {"id": 2, "value": 3, "row": 2, "start": 2, "end": 3, "part": true, "gears": []}
+  ],
+  "symbols": [
+    {"id": 0, "symbol": "*", "row": 0, "column": 2, "gear_score": null},
+    {"id": 1, "symbol": "#", "row": 1, "column": 2, "gear_score": null}
+  ],
+  "edges": [
+    {"number": 0, "symbol": 0},
+    {"number": 0, "symbol": 1},
+    {"number": 2, "symbol": 1}
+  ],
+  "part_number_sum": 15,
+  "gear_score_sum": 0
+}
+"##
+        );
     }
 
-    println!("sum: {}", sum);
+    #[test]
+    fn test_edits_match_rebuilding() {
+        // arrange
+        let mut rng = Rng::new(47);
+        for case in 0..50 {
+            let rules = Rules {
+                radius: rng.range(0, 3) as usize,
+                gear_part_count: rng.range(1, 3) as usize,
+                gear_credit: rng.pick(&[GearCredit::First, GearCredit::All]),
+                ..Rules::default()
+            };
+            let mut schematic =
+                EditableSchematic::new(crate::generate::generate(3, case, 12), &rules);
+            for _ in 0..20 {
+                let edits: Vec<Edit> = (0..rng.range(1, 4))
+                    .map(|_| Edit {
+                        row: rng.index(schematic.get_lines().len()),
+                        column: rng.index(14),
+                        byte: rng.pick(b"..0123456789*#"),
+                    })
+                    .collect();
+
+                // act
+                schematic.apply_edits(&edits);
+
+                // assert
+                let rebuilt = Schematic::build(schematic.get_lines().to_vec(), &rules);
+                assert_eq!(
+                    (
+                        schematic.get_part_number_sum(),
+                        schematic.get_gear_score_sum()
+                    ),
+                    (rebuilt.get_part_number_sum(), rebuilt.get_gear_score_sum()),
+                    "{:?} after {:?}",
+                    rules,
+                    edits
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_edit_only_looks_at_nearby_rows() {
+        // arrange
+        let lines = crate::examples::example_lines(3, 1).unwrap();
+        let mut schematic = EditableSchematic::new(lines, &Rules::default());
+
+        // act
+        // removing the gear between 467 and 35
+        let num_rows = schematic.apply_edits(&[Edit {
+            row: 1,
+            column: 3,
+            byte: b'.',
+        }]);
+
+        // assert
+        assert_eq!(num_rows, 4);
+        assert_eq!(schematic.get_part_number_sum(), 4361 - 467 - 35);
+        assert_eq!(schematic.get_gear_score_sum(), 467835 - 467 * 35);
+    }
 }
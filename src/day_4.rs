@@ -1,21 +1,28 @@
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::{HashSet, LinkedList};
+use std::collections::{HashSet, VecDeque};
 
 lazy_static! {
     static ref TOKEN_PATTERN: Regex = Regex::new(r"\d+|\|").unwrap();
 }
 
-pub fn step_1(lines: impl IntoIterator<Item = String>) {
-    let mut sum = 0;
+struct Card {
+    id: u64,
+    match_count: usize,
+}
 
-    for line in lines {
-        let mut token_iterator = TOKEN_PATTERN.find_iter(&line);
-        // skip the card number
-        token_iterator.next();
+impl Card {
+    fn parse(line: &str) -> Card {
+        let mut token_iterator = TOKEN_PATTERN.find_iter(line);
+        let id = token_iterator
+            .next()
+            .expect("missing card number")
+            .as_str()
+            .parse::<u64>()
+            .unwrap();
 
         let mut winning_numbers: HashSet<&str> = HashSet::new();
-        while let Some(m) = token_iterator.next() {
+        for m in token_iterator.by_ref() {
             let token = m.as_str();
             if token == "|" {
                 break;
@@ -23,65 +30,157 @@ pub fn step_1(lines: impl IntoIterator<Item = String>) {
             winning_numbers.insert(token);
         }
 
-        let mut points = 0;
-        while let Some(m) = token_iterator.next() {
-            if !winning_numbers.contains(m.as_str()) {
-                continue;
-            }
+        let match_count = token_iterator
+            .filter(|m| winning_numbers.contains(m.as_str()))
+            .count();
 
-            points = if points == 0 { 1 } else { points * 2 }
+        return Card { id, match_count };
+    }
+
+    fn get_points(&self) -> u128 {
+        if self.match_count == 0 {
+            return 0;
         }
+        return 1u128
+            .checked_shl(self.match_count as u32 - 1)
+            .expect("too many points to count");
+    }
+}
+
+fn parse_cards(lines: impl IntoIterator<Item = String>) -> Vec<Card> {
+    return lines.into_iter().map(|line| Card::parse(&line)).collect();
+}
 
-        sum += points;
+// each card's win is recorded once, as extra copies that apply to the next cards until they run
+// out, so this is linear in the number of cards however many they win
+fn get_copy_counts(cards: &[Card]) -> Vec<u128> {
+    let mut copy_counts: Vec<u128> = Vec::with_capacity(cards.len());
+    let mut num_extra_copies: u128 = 0;
+    // how many of the extra copies stop applying after each of the next cards
+    let mut expiring_copies: VecDeque<u128> = VecDeque::new();
+
+    for card in cards {
+        let num_copies = num_extra_copies
+            .checked_add(1)
+            .expect("too many copies to count");
+        copy_counts.push(num_copies);
+
+        num_extra_copies -= expiring_copies.pop_front().unwrap_or(0);
+        if card.match_count > 0 {
+            num_extra_copies = num_extra_copies
+                .checked_add(num_copies)
+                .expect("too many copies to count");
+            if expiring_copies.len() < card.match_count {
+                expiring_copies.resize(card.match_count, 0);
+            }
+            expiring_copies[card.match_count - 1] += num_copies;
+        }
     }
 
-    println!("sum: {}", sum);
+    return copy_counts;
+}
+
+pub fn solve_step_1(lines: impl IntoIterator<Item = String>) -> u128 {
+    return parse_cards(lines)
+        .iter()
+        .map(|card| card.get_points())
+        .sum();
+}
+
+pub fn step_1(lines: impl IntoIterator<Item = String>) {
+    println!("sum: {}", solve_step_1(lines));
+}
+
+pub fn solve_step_2(lines: impl IntoIterator<Item = String>) -> u128 {
+    let copy_counts = get_copy_counts(&parse_cards(lines));
+    return copy_counts
+        .into_iter()
+        .try_fold(0u128, |sum, num_copies| sum.checked_add(num_copies))
+        .expect("too many cards to count");
 }
 
 pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    let mut num_cards = 0;
-    let mut extra_copies_counts: LinkedList<i32> = LinkedList::new();
+    println!("number of cards: {}", solve_step_2(lines));
+}
 
-    for line in lines {
-        let mut token_iterator = TOKEN_PATTERN.find_iter(&line);
-        // skip the card number
-        token_iterator.next();
+pub fn print_report(lines: impl IntoIterator<Item = String>) {
+    let cards = parse_cards(lines);
+    let copy_counts = get_copy_counts(&cards);
+
+    let mut points_sum: u128 = 0;
+    let mut num_cards: u128 = 0;
+    println!(
+        "{:>6} {:>8} {:>12} {:>12}",
+        "card", "matches", "points", "copies"
+    );
+    for (card, num_copies) in cards.iter().zip(&copy_counts) {
+        let points = card.get_points();
+        points_sum += points;
+        num_cards = num_cards
+            .checked_add(*num_copies)
+            .expect("too many cards to count");
+        println!(
+            "{:>6} {:>8} {:>12} {:>12}",
+            card.id, card.match_count, points, num_copies
+        );
+    }
+    println!();
+    println!("sum of points: {}", points_sum);
+    println!("number of cards: {}", num_cards);
+}
 
-        let num_extra_copies = match extra_copies_counts.pop_front() {
-            Some(value) => value,
-            None => 0,
-        };
-        let multiplier = num_extra_copies + 1;
-        num_cards += multiplier;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let mut winning_numbers: HashSet<&str> = HashSet::new();
-        while let Some(m) = token_iterator.next() {
-            let token = m.as_str();
-            if token == "|" {
-                break;
+    fn count_copies_directly(cards: &[Card]) -> Vec<u128> {
+        let mut copy_counts: Vec<u128> = vec![1; cards.len()];
+        for (card_index, card) in cards.iter().enumerate() {
+            let end = std::cmp::min(card_index + 1 + card.match_count, cards.len());
+            for won_index in (card_index + 1)..end {
+                copy_counts[won_index] += copy_counts[card_index];
             }
-            winning_numbers.insert(token);
         }
+        return copy_counts;
+    }
 
-        let mut cards_earned = 0;
-        while let Some(m) = token_iterator.next() {
-            if winning_numbers.contains(m.as_str()) {
-                cards_earned += 1;
-            }
-        }
+    #[test]
+    fn test_example() {
+        // arrange
+        let lines = crate::examples::example_lines(4, 1).unwrap();
 
-        for extra_copies in extra_copies_counts.iter_mut() {
-            if cards_earned == 0 {
-                break;
-            }
+        // act
+        let result = (solve_step_1(lines.clone()), solve_step_2(lines));
 
-            cards_earned -= 1;
-            *extra_copies += multiplier;
-        }
-        for _ in 0..cards_earned {
-            extra_copies_counts.push_back(multiplier);
+        // assert
+        assert_eq!(result, (13, 30));
+    }
+
+    #[test]
+    fn test_copy_counts_match_direct_count() {
+        for seed in 0..20 {
+            // arrange
+            let cards = parse_cards(crate::generate::generate(4, seed, 50));
+
+            // act
+            let result = get_copy_counts(&cards);
+
+            // assert
+            assert_eq!(result, count_copies_directly(&cards));
         }
     }
 
-    println!("number of cards: {}", num_cards);
+    #[test]
+    fn test_long_chain_does_not_overflow() {
+        // arrange
+        // every card wins the next two, so the copies grow like the Fibonacci numbers, well past
+        // what fits in a u64
+        let lines = (1..=100).map(|id| format!("Card {}: 1 2 | 1 2", id));
+
+        // act
+        let result = get_copy_counts(&parse_cards(lines));
+
+        // assert
+        assert_eq!(result[99], 927372692193078999175);
+    }
 }
@@ -4,6 +4,7 @@ use std::vec::Vec;
 
 lazy_static! {
     static ref NUMBER_PATTERN: Regex = Regex::new(r"\d+").unwrap();
+    static ref HEADER_PATTERN: Regex = Regex::new(r"^(\w+)-to-(\w+) map:$").unwrap();
 }
 
 struct RangeMapping {
@@ -46,6 +47,14 @@ impl RangeMapping {
 
         return (updated_ranges, non_updated_ranges);
     }
+
+    fn invert(&self) -> RangeMapping {
+        return RangeMapping {
+            destination_start: self.source_start,
+            source_start: self.destination_start,
+            range_length: self.range_length,
+        };
+    }
 }
 
 struct RangeMappingBatchesIterator<'a> {
@@ -56,7 +65,7 @@ impl<'a> Iterator for RangeMappingBatchesIterator<'a> {
     type Item = Vec<RangeMapping>;
 
     fn next(&mut self) -> Option<Vec<RangeMapping>> {
-        // skip section header
+        // skip section header, which validate checks
         if self.lines.next() == None {
             return None;
         }
@@ -86,7 +95,7 @@ impl<'a> Iterator for RangeMappingBatchesIterator<'a> {
     }
 }
 
-pub fn step_1<'a>(lines: impl IntoIterator<Item = String>) {
+pub fn solve_step_1(lines: impl IntoIterator<Item = String>) -> i64 {
     let mut lines_iterator = lines.into_iter();
 
     let mut values: Vec<i64> = NUMBER_PATTERN
@@ -113,7 +122,11 @@ pub fn step_1<'a>(lines: impl IntoIterator<Item = String>) {
         }
     }
 
-    println!("lowest location: {}", values.iter().min().unwrap());
+    return *values.iter().min().unwrap();
+}
+
+pub fn step_1<'a>(lines: impl IntoIterator<Item = String>) {
+    println!("lowest location: {}", solve_step_1(lines));
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -158,7 +171,7 @@ impl Range {
     }
 }
 
-pub fn step_2(lines: impl IntoIterator<Item = String>) {
+pub fn solve_step_2(lines: impl IntoIterator<Item = String>) -> i64 {
     let mut lines_iterator = lines.into_iter();
     let seed_numbers: Vec<i64> = NUMBER_PATTERN
         .find_iter(&lines_iterator.next().unwrap())
@@ -207,10 +220,610 @@ pub fn step_2(lines: impl IntoIterator<Item = String>) {
         ranges = updated_ranges;
     }
 
+    return ranges.iter().map(|range| range.start).min().unwrap();
+}
+
+pub fn step_2(lines: impl IntoIterator<Item = String>) {
+    println!("lowest location: {}", solve_step_2(lines));
+}
+
+// past every range in a real almanac, so that one segment from 0 can cover every seed
+const DOMAIN_END: i64 = 1 << 62;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct Segment {
+    source: Range,
+    offset: i64,
+}
+
+// every map composed into one, as sorted segments that together cover every seed
+pub struct ComposedMap {
+    segments: Vec<Segment>,
+}
+
+impl ComposedMap {
+    fn identity() -> ComposedMap {
+        return ComposedMap {
+            segments: vec![Segment {
+                source: Range {
+                    start: 0,
+                    length: DOMAIN_END,
+                },
+                offset: 0,
+            }],
+        };
+    }
+
+    fn then(&self, range_mapping_batch: &[RangeMapping]) -> ComposedMap {
+        let mut segments: Vec<Segment> = Vec::new();
+        for segment in &self.segments {
+            let mut non_updated_ranges: Vec<Range> = vec![Range {
+                start: segment.source.start + segment.offset,
+                length: segment.source.length,
+            }];
+            for range_mapping in range_mapping_batch {
+                let delta = range_mapping.destination_start - range_mapping.source_start;
+                let (updated_ranges, still_non_updated_ranges) =
+                    range_mapping.apply_to_ranges(&non_updated_ranges);
+                for range in updated_ranges {
+                    segments.push(Segment {
+                        source: Range {
+                            start: range.start - delta - segment.offset,
+                            length: range.length,
+                        },
+                        offset: segment.offset + delta,
+                    });
+                }
+                non_updated_ranges = still_non_updated_ranges;
+            }
+            for range in non_updated_ranges {
+                segments.push(Segment {
+                    source: Range {
+                        start: range.start - segment.offset,
+                        length: range.length,
+                    },
+                    offset: segment.offset,
+                });
+            }
+        }
+
+        segments.sort_by_key(|segment| segment.source.start);
+
+        // merge neighbours that ended up with the same offset, so the table only splits where
+        // the function actually changes
+        let mut merged_segments: Vec<Segment> = Vec::new();
+        for segment in segments {
+            match merged_segments.last_mut() {
+                Some(last)
+                    if last.offset == segment.offset
+                        && last.source.start + last.source.length == segment.source.start =>
+                {
+                    last.source.length += segment.source.length;
+                }
+                _ => merged_segments.push(segment),
+            }
+        }
+
+        return ComposedMap {
+            segments: merged_segments,
+        };
+    }
+
+    fn get_segment_index(&self, seed: i64) -> usize {
+        // the segments are sorted and cover every seed, so the last one starting at or before
+        // the seed contains it
+        return self
+            .segments
+            .partition_point(|segment| segment.source.start <= seed)
+            - 1;
+    }
+
+    pub fn get_location(&self, seed: i64) -> i64 {
+        return seed + self.segments[self.get_segment_index(seed)].offset;
+    }
+
+    // within a segment the lowest location always comes from the lowest seed
+    fn get_lowest_location(&self, range: Range) -> i64 {
+        let mut lowest_location = i64::MAX;
+        for segment in &self.segments[self.get_segment_index(range.start)..] {
+            if let (_, Some(overlap), _) = range.split(segment.source) {
+                lowest_location = std::cmp::min(lowest_location, overlap.start + segment.offset);
+            } else {
+                break;
+            }
+        }
+        return lowest_location;
+    }
+
+    pub fn print(&self) {
+        println!("{:>12} {:>12} {:>12}", "seed start", "seed end", "offset");
+        for segment in &self.segments {
+            let end = segment.source.start + segment.source.length;
+            if end == DOMAIN_END {
+                println!(
+                    "{:>12} {:>12} {:>+12}",
+                    segment.source.start, "and up", segment.offset
+                );
+            } else {
+                println!(
+                    "{:>12} {:>12} {:>+12}",
+                    segment.source.start,
+                    end - 1,
+                    segment.offset
+                );
+            }
+        }
+    }
+}
+
+pub fn parse_composed(lines: impl IntoIterator<Item = String>) -> (Vec<i64>, ComposedMap) {
+    let mut lines_iterator = lines.into_iter();
+    let seed_numbers: Vec<i64> = NUMBER_PATTERN
+        .find_iter(&lines_iterator.next().unwrap())
+        .map(|m| m.as_str().parse::<i64>().unwrap())
+        .collect();
+
+    // skip empty line
+    lines_iterator.next();
+
+    let range_mapping_batches_iterator = RangeMappingBatchesIterator {
+        lines: Box::new(lines_iterator),
+    };
+    let mut composed_map = ComposedMap::identity();
+    for range_mapping_batch in range_mapping_batches_iterator {
+        composed_map = composed_map.then(&range_mapping_batch);
+    }
+
+    return (seed_numbers, composed_map);
+}
+
+pub fn solve_composed(lines: impl IntoIterator<Item = String>) -> (i64, i64) {
+    let (seed_numbers, composed_map) = parse_composed(lines);
+
+    let step_1_answer = seed_numbers
+        .iter()
+        .map(|seed| composed_map.get_location(*seed))
+        .min()
+        .unwrap();
+    let step_2_answer = seed_numbers
+        .chunks(2)
+        .map(|pair| {
+            composed_map.get_lowest_location(Range {
+                start: pair[0],
+                length: pair[1],
+            })
+        })
+        .min()
+        .unwrap();
+
+    return (step_1_answer, step_2_answer);
+}
+
+pub fn print_composed(lines: impl IntoIterator<Item = String>) {
+    let lines: Vec<String> = lines.into_iter().collect();
+    let (_, composed_map) = parse_composed(lines.clone());
+    composed_map.print();
+
+    let (step_1_answer, step_2_answer) = solve_composed(lines);
+    println!();
+    println!("step 1 lowest location: {}", step_1_answer);
+    println!("step 2 lowest location: {}", step_2_answer);
+}
+
+fn merge_ranges(mut ranges: Vec<Range>) -> Vec<Range> {
+    ranges.retain(|range| range.length > 0);
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged_ranges: Vec<Range> = Vec::new();
+    for range in ranges {
+        match merged_ranges.last_mut() {
+            Some(last) if last.start + last.length >= range.start => {
+                last.length = std::cmp::max(last.length, range.start + range.length - last.start);
+            }
+            _ => merged_ranges.push(range),
+        }
+    }
+    return merged_ranges;
+}
+
+// like the rest of this file, this assumes the sources within a batch don't overlap
+fn get_preimage(range_mapping_batch: &[RangeMapping], ranges: &Vec<Range>) -> Vec<Range> {
+    let mut preimage: Vec<Range> = Vec::new();
+    // a value that's in a mapping's source always gets moved, so only values outside every
+    // source can map to themselves
+    let mut unmapped_ranges: Vec<Range> = ranges.clone();
+    for range_mapping in range_mapping_batch {
+        let (source_ranges, _) = range_mapping.invert().apply_to_ranges(ranges);
+        preimage.extend(source_ranges);
+        let (_, still_unmapped_ranges) = range_mapping.apply_to_ranges(&unmapped_ranges);
+        unmapped_ranges = still_unmapped_ranges;
+    }
+    preimage.extend(unmapped_ranges);
+    return merge_ranges(preimage);
+}
+
+pub struct Almanac {
+    seed_numbers: Vec<i64>,
+    range_mapping_batches: Vec<Vec<RangeMapping>>,
+}
+
+impl Almanac {
+    pub fn parse(lines: impl IntoIterator<Item = String>) -> Almanac {
+        let mut lines_iterator = lines.into_iter();
+        let seed_numbers: Vec<i64> = NUMBER_PATTERN
+            .find_iter(&lines_iterator.next().unwrap())
+            .map(|m| m.as_str().parse::<i64>().unwrap())
+            .collect();
+
+        // skip empty line
+        lines_iterator.next();
+
+        let range_mapping_batches = RangeMappingBatchesIterator {
+            lines: Box::new(lines_iterator),
+        }
+        .collect();
+
+        return Almanac {
+            seed_numbers,
+            range_mapping_batches,
+        };
+    }
+
+    fn get_seed_ranges(&self) -> Vec<Range> {
+        return self
+            .seed_numbers
+            .chunks(2)
+            .map(|pair| Range {
+                start: pair[0],
+                length: pair[1],
+            })
+            .collect();
+    }
+
+    fn get_seeds_reaching(&self, locations: Range) -> Vec<Range> {
+        let mut ranges: Vec<Range> = vec![locations];
+        for range_mapping_batch in self.range_mapping_batches.iter().rev() {
+            ranges = get_preimage(range_mapping_batch, &ranges);
+        }
+        return ranges;
+    }
+
+    fn get_step_2_seeds_reaching(&self, locations: Range) -> Vec<(Range, Vec<Range>)> {
+        let seeds = self.get_seeds_reaching(locations);
+        return self
+            .get_seed_ranges()
+            .into_iter()
+            .map(|seed_range| {
+                let reaching_seeds = seeds
+                    .iter()
+                    .filter_map(|range| seed_range.split(*range).1)
+                    .collect();
+                (seed_range, reaching_seeds)
+            })
+            .collect();
+    }
+
+    // searches for the smallest `x` such that some seed reaches a location below it
+    pub fn get_lowest_step_2_location(&self) -> i64 {
+        let reaches_below = |end: i64| {
+            self.get_step_2_seeds_reaching(Range {
+                start: 0,
+                length: end,
+            })
+            .iter()
+            .any(|(_, reaching_seeds)| !reaching_seeds.is_empty())
+        };
+
+        let mut low = 0;
+        let mut high = DOMAIN_END;
+        while high - low > 1 {
+            let middle = low + (high - low) / 2;
+            if reaches_below(middle) {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+        return low;
+    }
+}
+
+pub fn solve_step_2_inverse(lines: impl IntoIterator<Item = String>) -> i64 {
+    return Almanac::parse(lines).get_lowest_step_2_location();
+}
+
+fn format_range(range: &Range) -> String {
+    return format!("{}..{}", range.start, range.start + range.length);
+}
+
+fn parse_locations(locations_str: &str) -> Range {
+    let parse = |number: &str| number.parse::<i64>().expect("could not parse location");
+    let (start, end) = match locations_str.split_once("..") {
+        Some(("", end)) => (0, parse(end)),
+        Some((start, end)) => (parse(start), parse(end)),
+        None => (parse(locations_str), parse(locations_str) + 1),
+    };
+    if start < 0 || end <= start {
+        panic!("empty location range: {}", locations_str);
+    }
+    return Range {
+        start,
+        length: end - start,
+    };
+}
+
+pub fn print_inverse(lines: impl IntoIterator<Item = String>, locations_str: &str) {
+    let almanac = Almanac::parse(lines);
+    let locations = parse_locations(locations_str);
+
+    println!("seeds reaching locations {}:", format_range(&locations));
+    for range in almanac.get_seeds_reaching(locations) {
+        println!("  {}", format_range(&range));
+    }
+    println!();
+    println!("step 2 seed ranges reaching them:");
+    for (seed_range, reaching_seeds) in almanac.get_step_2_seeds_reaching(locations) {
+        if reaching_seeds.is_empty() {
+            continue;
+        }
+        let reaching_seeds: Vec<String> = reaching_seeds.iter().map(format_range).collect();
+        println!(
+            "  {}: {}",
+            format_range(&seed_range),
+            reaching_seeds.join(", ")
+        );
+    }
+    println!();
     println!(
-        "lowest location: {}",
-        ranges.iter().map(|range| range.start).min().unwrap()
+        "step 2 lowest location: {}",
+        almanac.get_lowest_step_2_location()
+    );
+}
+
+// line numbers are counted from 1
+struct Section {
+    line_number: usize,
+    source_category: String,
+    destination_category: String,
+    range_mappings: Vec<(usize, RangeMapping)>,
+}
+
+fn parse_numbers(line: &str) -> Result<Vec<i64>, String> {
+    if let Some(token) = line
+        .split_whitespace()
+        .find(|token| !token.bytes().all(|byte| byte.is_ascii_digit()))
+    {
+        return Err(format!("{} is not a number", token));
+    }
+    return line
+        .split_whitespace()
+        .map(|token| {
+            token
+                .parse::<i64>()
+                .map_err(|_| format!("{} doesn't fit in an i64", token))
+        })
+        .collect();
+}
+
+// returns the sections it could read and the problems, each with its line number
+fn validate(lines: impl IntoIterator<Item = String>) -> (Vec<Section>, Vec<(usize, String)>) {
+    let mut problems: Vec<(usize, String)> = Vec::new();
+    let mut sections: Vec<Section> = Vec::new();
+    let mut lines_iterator = lines
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line));
+
+    match lines_iterator.next() {
+        Some((line_number, line)) if line.starts_with("seeds:") => {
+            match parse_numbers(&line["seeds:".len()..]) {
+                Ok(seed_numbers) if seed_numbers.len() % 2 != 0 => problems.push((
+                    line_number,
+                    format!(
+                        "{} seed numbers can't be paired up into ranges for step 2",
+                        seed_numbers.len()
+                    ),
+                )),
+                Ok(seed_numbers) => {
+                    for pair in seed_numbers.chunks(2) {
+                        if pair[0].checked_add(pair[1]).is_none() {
+                            problems.push((
+                                line_number,
+                                format!("seed range {} {} overflows an i64", pair[0], pair[1]),
+                            ));
+                        }
+                    }
+                }
+                Err(message) => problems.push((line_number, message)),
+            }
+        }
+        Some((line_number, _)) => {
+            problems.push((line_number, "expected the seeds: line".to_owned()))
+        }
+        None => problems.push((1, "the almanac is empty".to_owned())),
+    }
+    match lines_iterator.next() {
+        Some((line_number, line)) if !line.is_empty() => problems.push((
+            line_number,
+            "expected an empty line after the seeds".to_owned(),
+        )),
+        _ => {}
+    }
+
+    let mut current_section: Option<Section> = None;
+    for (line_number, line) in lines_iterator {
+        if line.is_empty() {
+            match current_section.take() {
+                Some(section) => sections.push(section),
+                None => problems.push((line_number, "unexpected empty line".to_owned())),
+            }
+            continue;
+        }
+
+        let Some(section) = &mut current_section else {
+            let (source_category, destination_category) = match HEADER_PATTERN.captures(&line) {
+                Some(captures) => (captures[1].to_owned(), captures[2].to_owned()),
+                None => {
+                    problems.push((
+                        line_number,
+                        format!("expected a header like x-to-y map:, got {}", line),
+                    ));
+                    ("?".to_owned(), "?".to_owned())
+                }
+            };
+            current_section = Some(Section {
+                line_number,
+                source_category,
+                destination_category,
+                range_mappings: Vec::new(),
+            });
+            continue;
+        };
+
+        let numbers = match parse_numbers(&line) {
+            Ok(numbers) if numbers.len() == 3 => numbers,
+            Ok(numbers) => {
+                problems.push((
+                    line_number,
+                    format!("expected 3 numbers, got {}", numbers.len()),
+                ));
+                continue;
+            }
+            Err(message) => {
+                problems.push((line_number, message));
+                continue;
+            }
+        };
+        let range_mapping = RangeMapping {
+            destination_start: numbers[0],
+            source_start: numbers[1],
+            range_length: numbers[2],
+        };
+        if range_mapping.range_length == 0 {
+            problems.push((line_number, "mapping has zero length".to_owned()));
+        }
+        for (name, start) in [
+            ("source", range_mapping.source_start),
+            ("destination", range_mapping.destination_start),
+        ] {
+            match start.checked_add(range_mapping.range_length) {
+                None => problems.push((line_number, format!("{} range overflows an i64", name))),
+                Some(end) if end > DOMAIN_END => problems.push((
+                    line_number,
+                    format!(
+                        "{} range ends past {}, which the composed map doesn't cover",
+                        name, DOMAIN_END
+                    ),
+                )),
+                _ => {}
+            }
+        }
+        section.range_mappings.push((line_number, range_mapping));
+    }
+    sections.extend(current_section);
+
+    let mut expected_category = "seed";
+    for section in &sections {
+        if section.source_category != expected_category && section.source_category != "?" {
+            problems.push((
+                section.line_number,
+                format!(
+                    "section maps from {}, but the previous one maps to {}",
+                    section.source_category, expected_category
+                ),
+            ));
+        }
+        expected_category = &section.destination_category;
+    }
+    if expected_category != "location" && expected_category != "?" {
+        problems.push((
+            sections.last().map_or(1, |section| section.line_number),
+            format!(
+                "the last section maps to {} instead of location",
+                expected_category
+            ),
+        ));
+    }
+
+    for section in &sections {
+        let mut range_mappings: Vec<&(usize, RangeMapping)> =
+            section.range_mappings.iter().collect();
+        range_mappings.sort_by_key(|(_, range_mapping)| range_mapping.source_start);
+        // the mapping that reaches furthest so far, which is the one anything after it would
+        // overlap first. ends are i128 so overflowing mappings can still be compared
+        let mut furthest: Option<(usize, i128)> = None;
+        for (line_number, range_mapping) in range_mappings {
+            let start = range_mapping.source_start as i128;
+            let end = start + range_mapping.range_length as i128;
+            match furthest {
+                Some((furthest_line_number, furthest_end)) if furthest_end > start => {
+                    problems.push((
+                        *line_number,
+                        format!(
+                            "source range overlaps the one on line {}",
+                            furthest_line_number
+                        ),
+                    ));
+                    if end > furthest_end {
+                        furthest = Some((*line_number, end));
+                    }
+                }
+                _ => furthest = Some((*line_number, end)),
+            }
+        }
+    }
+
+    problems.sort();
+    return (sections, problems);
+}
+
+fn get_gaps(section: &Section) -> Vec<Range> {
+    let source_ranges = merge_ranges(
+        section
+            .range_mappings
+            .iter()
+            .map(|(_, range_mapping)| Range {
+                start: range_mapping.source_start,
+                length: range_mapping.range_length,
+            })
+            .collect(),
     );
+    return source_ranges
+        .windows(2)
+        .map(|pair| Range {
+            start: pair[0].start + pair[0].length,
+            length: pair[1].start - pair[0].start - pair[0].length,
+        })
+        .collect();
+}
+
+pub fn print_validation(lines: impl IntoIterator<Item = String>) {
+    let (sections, problems) = validate(lines);
+
+    for section in &sections {
+        let gaps: Vec<String> = get_gaps(section).iter().map(format_range).collect();
+        println!(
+            "line {}: {}-to-{}, {} mappings, {}",
+            section.line_number,
+            section.source_category,
+            section.destination_category,
+            section.range_mappings.len(),
+            if gaps.is_empty() {
+                "no gaps".to_owned()
+            } else {
+                format!("gaps at {}", gaps.join(", "))
+            }
+        );
+    }
+    println!();
+    for (line_number, message) in &problems {
+        println!("line {}: {}", line_number, message);
+    }
+    match problems.len() {
+        0 => println!("no problems found"),
+        1 => println!("1 problem found"),
+        num_problems => println!("{} problems found", num_problems),
+    }
 }
 
 #[cfg(test)]
@@ -460,4 +1073,158 @@ mod tests {
             )
         )
     }
+
+    #[test]
+    fn test_composed_map_example() {
+        // arrange
+        let lines = crate::examples::example_lines(5, 1).unwrap();
+
+        // act
+        let result = solve_composed(lines);
+
+        // assert
+        assert_eq!(result, (35, 46));
+    }
+
+    #[test]
+    fn test_composed_map_matches_batches() {
+        // arrange
+        let lines = crate::examples::example_lines(5, 1).unwrap();
+        let (_, composed_map) = parse_composed(lines);
+
+        // act
+        let result: Vec<i64> = [79, 14, 55, 13]
+            .into_iter()
+            .map(|seed| composed_map.get_location(seed))
+            .collect();
+
+        // assert
+        assert_eq!(result, vec![82, 43, 86, 35]);
+    }
+
+    #[test]
+    fn test_seeds_reaching_location() {
+        // arrange
+        let lines = crate::examples::example_lines(5, 1).unwrap();
+        let almanac = Almanac::parse(lines.clone());
+        let (_, composed_map) = parse_composed(lines);
+
+        for location in 0..120 {
+            // act
+            let result = almanac.get_seeds_reaching(Range {
+                start: location,
+                length: 1,
+            });
+
+            // assert
+            let expected: Vec<i64> = (0..120)
+                .filter(|seed| composed_map.get_location(*seed) == location)
+                .collect();
+            let seeds: Vec<i64> = result
+                .iter()
+                .flat_map(|range| range.start..std::cmp::min(range.start + range.length, 120))
+                .collect();
+            assert_eq!(seeds, expected, "location {}", location);
+        }
+    }
+
+    #[test]
+    fn test_inverse_example() {
+        // arrange
+        let lines = crate::examples::example_lines(5, 1).unwrap();
+
+        // act
+        let result = solve_step_2_inverse(lines);
+
+        // assert
+        assert_eq!(result, 46);
+    }
+
+    #[test]
+    fn test_validate_broken_chain() {
+        // arrange
+        let lines = "seeds: 1 2
+
+seed-to-soil map:
+0 1 2
+
+water-to-light map:
+0 1 2"
+            .lines()
+            .map(|line| line.to_owned());
+
+        // act
+        let (_, problems) = validate(lines);
+
+        // assert
+        assert_eq!(
+            problems,
+            vec![
+                (
+                    6,
+                    "section maps from water, but the previous one maps to soil".to_owned()
+                ),
+                (
+                    6,
+                    "the last section maps to light instead of location".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_example() {
+        // arrange
+        let lines = crate::examples::example_lines(5, 1).unwrap();
+
+        // act
+        let (sections, problems) = validate(lines);
+
+        // assert
+        assert_eq!(sections.len(), 7);
+        assert_eq!(problems, vec![]);
+    }
+
+    #[test]
+    fn test_validate_finds_problems() {
+        // arrange
+        let lines = "seeds: 79 14 55
+1
+seed-to-soil map:
+50 98 2
+52 50 0
+52 90 10
+1 2
+
+soil-to-water map:
+0 9223372036854775807 1
+
+fertilizer-to-location map
+0 1 2"
+            .lines()
+            .map(|line| line.to_owned());
+
+        // act
+        let (_, problems) = validate(lines);
+
+        // assert
+        assert_eq!(
+            problems,
+            vec![
+                (
+                    1,
+                    "3 seed numbers can't be paired up into ranges for step 2".to_owned()
+                ),
+                (2, "expected an empty line after the seeds".to_owned()),
+                (4, "source range overlaps the one on line 6".to_owned()),
+                (5, "mapping has zero length".to_owned()),
+                (7, "expected 3 numbers, got 2".to_owned()),
+                (10, "source range overflows an i64".to_owned()),
+                (
+                    12,
+                    "expected a header like x-to-y map:, got fertilizer-to-location map".to_owned()
+                ),
+            ]
+        );
+    }
 }
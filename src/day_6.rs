@@ -23,7 +23,7 @@ fn get_num_valid_integer_hold_times(time: i64, distance: i64) -> i64 {
     return time - 1 - (minimum_hold_time.floor() as i64) * 2;
 }
 
-pub fn step_1(lines: impl IntoIterator<Item = String>) {
+pub fn solve_step_1(lines: impl IntoIterator<Item = String>) -> i64 {
     let mut lines_iterator = lines.into_iter();
     let times_string = lines_iterator.next().unwrap();
     let distances_string = lines_iterator.next().unwrap();
@@ -40,10 +40,14 @@ pub fn step_1(lines: impl IntoIterator<Item = String>) {
         product *= get_num_valid_integer_hold_times(time, distance);
     }
 
-    println!("product: {}", product);
+    return product;
 }
 
-pub fn step_2(lines: impl IntoIterator<Item = String>) {
+pub fn step_1(lines: impl IntoIterator<Item = String>) {
+    println!("product: {}", solve_step_1(lines));
+}
+
+pub fn solve_step_2(lines: impl IntoIterator<Item = String>) -> i64 {
     let mut lines_iterator = lines.into_iter();
     let time_string = lines_iterator.next().unwrap();
     let distance_string = lines_iterator.next().unwrap();
@@ -61,8 +65,28 @@ pub fn step_2(lines: impl IntoIterator<Item = String>) {
         .parse::<i64>()
         .unwrap();
 
-    println!(
-        "result: {}",
-        get_num_valid_integer_hold_times(time, distance)
-    );
+    return get_num_valid_integer_hold_times(time, distance);
+}
+
+pub fn step_2(lines: impl IntoIterator<Item = String>) {
+    println!("result: {}", solve_step_2(lines));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_2_on_generated_inputs() {
+        for seed in 0..50 {
+            // arrange
+            let lines = crate::generate::generate(6, seed, 10);
+
+            // act
+            let result = solve_step_2(lines);
+
+            // assert
+            assert!(result > 0, "seed {}", seed);
+        }
+    }
 }
@@ -22,7 +22,17 @@ struct Hand {
     bid: i32,
 }
 
-fn get_hand_type_value(hand: &str, is_step_2: bool) -> i32 {
+pub const HAND_TYPE_NAMES: [&str; 7] = [
+    "high card",
+    "one pair",
+    "two pair",
+    "three of a kind",
+    "full house",
+    "four of a kind",
+    "five of a kind",
+];
+
+pub fn get_hand_type_value(hand: &str, is_step_2: bool) -> i32 {
     let mut wildcards = 0;
     let mut counts: HashMap<u8, i32> = HashMap::new();
     for byte in hand.bytes() {
@@ -88,7 +98,7 @@ fn get_hand_type_value(hand: &str, is_step_2: bool) -> i32 {
 
 const CARDS_PER_SUIT: i32 = 13;
 
-fn evaluate_hand_value(hand: &str, is_step_2: bool) -> i32 {
+pub fn evaluate_hand_value(hand: &str, is_step_2: bool) -> i32 {
     return hand
         .bytes()
         .map(|byte| {
@@ -7,10 +7,11 @@ lazy_static! {
         Regex::new(r"(?<node>\w{3}) = \((?<left>\w{3}), (?<right>\w{3})\)").unwrap();
 }
 
-pub fn step_1(lines: impl IntoIterator<Item = String>) {
+fn parse_network(
+    lines: impl IntoIterator<Item = String>,
+) -> (Vec<u8>, HashMap<String, (String, String)>) {
     let mut lines_iterator = lines.into_iter();
-    let instructions_str = lines_iterator.next().unwrap();
-    let instructions = instructions_str.as_bytes();
+    let instructions = lines_iterator.next().unwrap().into_bytes();
     // skip empty line
     lines_iterator.next();
 
@@ -24,6 +25,12 @@ pub fn step_1(lines: impl IntoIterator<Item = String>) {
         node_map.insert(node, (left, right));
     }
 
+    return (instructions, node_map);
+}
+
+pub fn step_1(lines: impl IntoIterator<Item = String>) {
+    let (instructions, node_map) = parse_network(lines);
+
     let mut current = "AAA";
     let mut counter = 0;
     while current != "ZZZ" {
@@ -42,71 +49,57 @@ pub fn step_1(lines: impl IntoIterator<Item = String>) {
     println!("steps: {}", counter);
 }
 
-pub fn step_2(lines: impl IntoIterator<Item = String>) {
-    let mut lines_iterator = lines.into_iter();
-    let instructions_str = lines_iterator.next().unwrap();
-    let instructions = instructions_str.as_bytes();
-    // skip empty line
-    lines_iterator.next();
-
-    let mut starting_nodes: Vec<String> = Vec::new();
-    let mut node_map: HashMap<String, (String, String)> = HashMap::new();
-    while let Some(line) = lines_iterator.next() {
-        let captures = NODE_PATTERN.captures(&line).unwrap();
-        let node = captures.name("node").unwrap().as_str().to_owned();
-        let left = captures.name("left").unwrap().as_str().to_owned();
-        let right = captures.name("right").unwrap().as_str().to_owned();
-
-        if node.ends_with("A") {
-            starting_nodes.push(node.clone());
+// returns every node visited before the ghost gets back to a node at the same point in the
+// instructions, along with where the repeated part starts
+fn find_cycle(
+    start: &str,
+    instructions: &[u8],
+    node_map: &HashMap<String, (String, String)>,
+) -> (Vec<String>, usize) {
+    let mut current = start;
+    let mut path: Vec<String> = Vec::new();
+    let mut visited: HashMap<String, Vec<usize>> = HashMap::new();
+    loop {
+        let counter = path.len();
+        if let Some(indices) = visited.get_mut(current) {
+            for index in indices.iter() {
+                if index % instructions.len() == counter % instructions.len() {
+                    return (path, *index);
+                }
+            }
+            indices.push(counter);
+        } else {
+            visited.insert(current.to_string(), vec![counter]);
         }
+        path.push(current.to_string());
 
-        node_map.insert(node, (left, right));
+        let mapping = &node_map[current];
+        let instruction = instructions[counter % instructions.len()];
+
+        current = if instruction == b'L' {
+            &mapping.0
+        } else {
+            &mapping.1
+        };
     }
+}
 
-    for node in starting_nodes {
-        let mut current = &node;
-        let mut visited: HashMap<String, Vec<i32>> = HashMap::new();
-        let mut loop_start = 0;
-        let mut counter = 0;
-        loop {
-            let mut found_loop = false;
-            if let Some(indices) = visited.get_mut(current) {
-                for index in indices.iter() {
-                    if index % instructions.len() as i32 == counter % instructions.len() as i32 {
-                        loop_start = *index;
-                        found_loop = true;
-                        break;
-                    }
-                }
-                if !found_loop {
-                    indices.push(counter);
-                }
-            } else {
-                visited.insert(current.to_string(), vec![counter]);
-            }
+pub fn step_2(lines: impl IntoIterator<Item = String>) {
+    let (instructions, node_map) = parse_network(lines);
 
-            if found_loop {
-                break;
-            }
+    let mut starting_nodes: Vec<&String> =
+        node_map.keys().filter(|node| node.ends_with("A")).collect();
+    starting_nodes.sort();
 
+    for node in starting_nodes {
+        let (path, loop_start) = find_cycle(node, &instructions, &node_map);
+        for (counter, current) in path.iter().enumerate() {
             if current.ends_with("Z") {
                 println!("z at {}", counter);
             }
-
-            let mapping = &node_map[current];
-            let instruction = instructions[counter as usize % instructions.len()];
-
-            current = if instruction == b'L' {
-                &mapping.0
-            } else {
-                &mapping.1
-            };
-
-            counter += 1;
         }
 
-        println!("loop from {} to {}", loop_start, counter);
+        println!("loop from {} to {}", loop_start, path.len());
         println!();
     }
 }
@@ -134,3 +127,117 @@ pub fn step_2(lines: impl IntoIterator<Item = String>) {
 //
 // since there's only one z for each of these and loop_end - z == loop_start for each,
 // we just need the LCM of the z's, which is 21003205388413.
+
+const CYCLE_COLORS: [&str; 6] = [
+    "lightblue",
+    "palegreen",
+    "gold",
+    "pink",
+    "plum",
+    "lightsalmon",
+];
+
+// everything is sorted by node name so the output is the same on every run
+pub fn to_dot(lines: impl IntoIterator<Item = String>) -> String {
+    let (instructions, node_map) = parse_network(lines);
+
+    let mut nodes: Vec<&String> = node_map.keys().collect();
+    nodes.sort();
+
+    // a node can be on more than one ghost's cycle, in which case the first ghost wins
+    let mut cycle_colors: HashMap<&str, &str> = HashMap::new();
+    let mut comments: Vec<String> = Vec::new();
+    for (ghost_index, start) in nodes.iter().filter(|node| node.ends_with("A")).enumerate() {
+        let color = CYCLE_COLORS[ghost_index % CYCLE_COLORS.len()];
+        let (path, loop_start) = find_cycle(start, &instructions, &node_map);
+        comments.push(format!(
+            "  // {}: cycle from step {} to {} in {}",
+            start,
+            loop_start,
+            path.len(),
+            color
+        ));
+        for node in &path[loop_start..] {
+            let node = node_map.get_key_value(node).unwrap().0;
+            cycle_colors.entry(node).or_insert(color);
+        }
+    }
+
+    let mut dot = "digraph network {\n".to_owned();
+    for comment in comments {
+        dot += &comment;
+        dot += "\n";
+    }
+    for node in &nodes {
+        let mut attributes: Vec<String> = Vec::new();
+        if node.ends_with("A") {
+            attributes.push("shape=box".to_owned());
+        } else if node.ends_with("Z") {
+            attributes.push("shape=doublecircle".to_owned());
+        }
+        if let Some(color) = cycle_colors.get(node.as_str()) {
+            attributes.push(format!("style=filled, fillcolor={}", color));
+        }
+        if attributes.is_empty() {
+            dot += &format!("  \"{}\";\n", node);
+        } else {
+            dot += &format!("  \"{}\" [{}];\n", node, attributes.join(", "));
+        }
+    }
+    for node in &nodes {
+        let (left, right) = &node_map[*node];
+        if left == right {
+            dot += &format!("  \"{}\" -> \"{}\" [label=\"L/R\"];\n", node, left);
+        } else {
+            dot += &format!("  \"{}\" -> \"{}\" [label=\"L\"];\n", node, left);
+            dot += &format!("  \"{}\" -> \"{}\" [label=\"R\"];\n", node, right);
+        }
+    }
+    dot += "}\n";
+    return dot;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples;
+
+    #[test]
+    fn test_to_dot_snapshot() {
+        // arrange
+        let lines = examples::example_lines(8, 2).unwrap();
+
+        // act
+        let result = to_dot(lines);
+
+        // assert
+        assert_eq!(
+            result,
+            "digraph network {
+  // 11A: cycle from step 1 to 3 in lightblue
+  // 22A: cycle from step 1 to 7 in palegreen
+  \"11A\" [shape=box];
+  \"11B\" [style=filled, fillcolor=lightblue];
+  \"11Z\" [shape=doublecircle, style=filled, fillcolor=lightblue];
+  \"22A\" [shape=box];
+  \"22B\" [style=filled, fillcolor=palegreen];
+  \"22C\" [style=filled, fillcolor=palegreen];
+  \"22Z\" [shape=doublecircle, style=filled, fillcolor=palegreen];
+  \"XXX\";
+  \"11A\" -> \"11B\" [label=\"L\"];
+  \"11A\" -> \"XXX\" [label=\"R\"];
+  \"11B\" -> \"XXX\" [label=\"L\"];
+  \"11B\" -> \"11Z\" [label=\"R\"];
+  \"11Z\" -> \"11B\" [label=\"L\"];
+  \"11Z\" -> \"XXX\" [label=\"R\"];
+  \"22A\" -> \"22B\" [label=\"L\"];
+  \"22A\" -> \"XXX\" [label=\"R\"];
+  \"22B\" -> \"22C\" [label=\"L/R\"];
+  \"22C\" -> \"22Z\" [label=\"L/R\"];
+  \"22Z\" -> \"22B\" [label=\"L/R\"];
+  \"XXX\" -> \"XXX\" [label=\"L/R\"];
+}
+"
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use crate::examples;
+use crate::generate::Rng;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+const REPLACEMENT_BYTES: &[u8] = b"0123456789 .#-|,;:=()?*/\\LRSJ7FO\r\n";
+
+const WHITESPACE: [&[u8]; 5] = [b" ", b"  ", b"\t", b"\n", b"\n\n"];
+
+const HUGE_NUMBERS: [&str; 5] = [
+    "0",
+    "4294967296",
+    "9223372036854775807",
+    "9223372036854775808",
+    "99999999999999999999999",
+];
+
+const NUM_MUTATION_KINDS: u64 = 7;
+
+fn apply_mutation(rng: &mut Rng, mut bytes: Vec<u8>, kind: u64) -> Vec<u8> {
+    match kind {
+        0 if !bytes.is_empty() => {
+            let index = rng.index(bytes.len());
+            bytes[index] = rng.pick(REPLACEMENT_BYTES);
+        }
+        1 => {
+            let length = rng.index(bytes.len() + 1);
+            bytes.truncate(length);
+        }
+        2 => {
+            let index = rng.index(bytes.len() + 1);
+            bytes.splice(index..index, rng.pick(&WHITESPACE).iter().copied());
+        }
+        3 => {
+            let mut crlf_bytes = Vec::new();
+            for byte in bytes {
+                if byte == b'\n' {
+                    crlf_bytes.push(b'\r');
+                }
+                crlf_bytes.push(byte);
+            }
+            bytes = crlf_bytes;
+        }
+        4 => {
+            // replace a whole run of digits, so that the new number doesn't just get glued
+            // onto the old one
+            let digit_indices: Vec<usize> = (0..bytes.len())
+                .filter(|index| bytes[*index].is_ascii_digit())
+                .collect();
+            if digit_indices.is_empty() {
+                return bytes;
+            }
+            let mut start = rng.pick(&digit_indices);
+            while start > 0 && bytes[start - 1].is_ascii_digit() {
+                start -= 1;
+            }
+            let mut end = start;
+            while end < bytes.len() && bytes[end].is_ascii_digit() {
+                end += 1;
+            }
+            bytes.splice(start..end, rng.pick(&HUGE_NUMBERS).bytes());
+        }
+        5 => {
+            let mut lines: Vec<Vec<u8>> = bytes
+                .split(|byte| *byte == b'\n')
+                .map(|line| line.to_vec())
+                .collect();
+            let index = rng.index(lines.len());
+            if rng.chance(0.5) {
+                lines.remove(index);
+            } else {
+                let line = lines[index].clone();
+                lines.insert(index, line);
+            }
+            bytes = lines.join(&b'\n');
+        }
+        _ if bytes.len() > 1 => {
+            let a = rng.index(bytes.len());
+            let b = rng.index(bytes.len());
+            bytes.swap(a, b);
+        }
+        _ => {}
+    }
+
+    return bytes;
+}
+
+fn mutate(rng: &mut Rng, input: &[u8]) -> Vec<u8> {
+    let mut bytes = input.to_vec();
+    for _ in 0..rng.range(1, 4) {
+        let kind = rng.range(0, NUM_MUTATION_KINDS);
+        bytes = apply_mutation(rng, bytes, kind);
+    }
+
+    return bytes;
+}
+
+// main reads the input with BufRead::lines, which strips the \r of a \r\n. splitting on \n alone
+// keeps it, so that a stray \r actually reaches the solvers.
+fn split_lines(input: &[u8]) -> Vec<String> {
+    let text = String::from_utf8_lossy(input);
+    let mut lines: Vec<String> = text.split('\n').map(|line| line.to_owned()).collect();
+    // like BufRead::lines, a final newline doesn't start another line
+    if lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    return lines;
+}
+
+enum Outcome {
+    Finished,
+    Panicked(String),
+    Hung,
+}
+
+thread_local! {
+    static LAST_PANIC: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+fn run_case(day: u32, step: u32, input: &[u8], budget: Duration) -> Outcome {
+    let lines = split_lines(input);
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let command = format!("day-{}-step-{}", day, step);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            crate::solve(&command, lines.into_iter());
+        }));
+        let message = match result {
+            Ok(()) => None,
+            Err(_) => Some(LAST_PANIC.with(|last_panic| last_panic.borrow().clone())),
+        };
+        // the receiver is gone if we already gave up on this case
+        let _ = sender.send(message);
+    });
+
+    return match receiver.recv_timeout(budget) {
+        Ok(None) => Outcome::Finished,
+        Ok(Some(message)) => Outcome::Panicked(message),
+        Err(_) => Outcome::Hung,
+    };
+}
+
+fn save_input(corpus_dir: &Path, day: u32, step: u32, input: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    let path = corpus_dir.join(format!(
+        "day-{}-step-{}-{:016x}.txt",
+        day,
+        step,
+        hasher.finish()
+    ));
+    fs::write(&path, input).unwrap();
+    return path.display().to_string();
+}
+
+// the report goes to stderr, since the solvers print their answers to stdout. overflows are
+// only caught in debug builds
+pub fn fuzz(days: &[u32], seed: u64, count: u64, budget: Duration, corpus_dir: &str) {
+    let corpus_dir = Path::new(corpus_dir);
+    fs::create_dir_all(corpus_dir).unwrap();
+
+    panic::set_hook(Box::new(|info| {
+        let message = info.to_string().replace('\n', " ");
+        LAST_PANIC.with(|last_panic| *last_panic.borrow_mut() = message);
+    }));
+
+    let mut rng = Rng::new(seed);
+    let mut num_failures = 0;
+    for &day in days {
+        for step in [1, 2] {
+            let example = match examples::example(day, step) {
+                Some(example) => example.to_owned() + "\n",
+                None => continue,
+            };
+
+            let mut seen_messages: HashSet<String> = HashSet::new();
+            for _ in 0..count {
+                let input = mutate(&mut rng, example.as_bytes());
+                match run_case(day, step, &input, budget) {
+                    Outcome::Finished => {}
+                    Outcome::Panicked(message) => {
+                        // only report the first input for each panic site
+                        let site = message.split(' ').take(3).collect::<Vec<&str>>().join(" ");
+                        if seen_messages.insert(site) {
+                            num_failures += 1;
+                            let path = save_input(corpus_dir, day, step, &input);
+                            eprintln!("day {} step {}: {} ({})", day, step, message, path);
+                        }
+                    }
+                    Outcome::Hung => {
+                        num_failures += 1;
+                        let path = save_input(corpus_dir, day, step, &input);
+                        eprintln!(
+                            "day {} step {}: still running after {:?}, skipping the rest of this step ({})",
+                            day, step, budget, path
+                        );
+                        // the hung thread can't be stopped, so don't pile more on top of it
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    let _ = panic::take_hook();
+    eprintln!("{} distinct failures", num_failures);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &[u8] = b"Time: 7 15 30\nDistance: 9 40 200\n";
+
+    fn without_whitespace(bytes: &[u8]) -> Vec<u8> {
+        return bytes
+            .iter()
+            .copied()
+            .filter(|byte| !byte.is_ascii_whitespace())
+            .collect();
+    }
+
+    #[test]
+    fn test_mutate_is_deterministic() {
+        for seed in 0..20 {
+            // act
+            let result = (
+                mutate(&mut Rng::new(seed), INPUT),
+                mutate(&mut Rng::new(seed), INPUT),
+            );
+
+            // assert
+            assert_eq!(result.0, result.1);
+        }
+    }
+
+    #[test]
+    fn test_each_kind_of_mutation() {
+        for seed in 0..20 {
+            // act
+            let result: Vec<Vec<u8>> = (0..NUM_MUTATION_KINDS)
+                .map(|kind| apply_mutation(&mut Rng::new(seed), INPUT.to_vec(), kind))
+                .collect();
+
+            // assert
+            // replaces one byte
+            assert_eq!(result[0].len(), INPUT.len());
+            assert!(result[0].iter().zip(INPUT).filter(|(a, b)| a != b).count() <= 1);
+            // truncates
+            assert!(INPUT.starts_with(&result[1]));
+            // inserts whitespace
+            assert!(result[2].len() > INPUT.len());
+            assert_eq!(without_whitespace(&result[2]), without_whitespace(INPUT));
+            // switches to CRLF
+            assert_eq!(result[3], b"Time: 7 15 30\r\nDistance: 9 40 200\r\n");
+            // swaps in a huge number
+            assert_ne!(result[4], INPUT);
+            let text = String::from_utf8(result[4].clone()).unwrap();
+            assert!(HUGE_NUMBERS.iter().any(|number| text.contains(number)));
+            // removes or repeats a line
+            let num_lines = result[5].iter().filter(|byte| **byte == b'\n').count();
+            assert!(num_lines == 1 || num_lines == 3);
+            // swaps two bytes
+            let (mut sorted, mut expected) = (result[6].clone(), INPUT.to_vec());
+            sorted.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(sorted, expected);
+        }
+    }
+
+    #[test]
+    fn test_split_lines_keeps_carriage_returns() {
+        // act
+        let result = split_lines(b"a\r\nb\n\nc\r\n");
+
+        // assert
+        assert_eq!(result, vec!["a\r", "b", "", "c\r"]);
+    }
+}
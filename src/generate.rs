@@ -0,0 +1,998 @@
+use std::collections::HashSet;
+use std::vec::Vec;
+
+// splitmix64, so that generated inputs are reproducible without an external crate
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        return Rng { state: seed };
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        return z ^ (z >> 31);
+    }
+
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        if high <= low {
+            return low;
+        }
+        return low + self.next_u64() % (high - low);
+    }
+
+    pub fn index(&mut self, length: usize) -> usize {
+        return self.range(0, length as u64) as usize;
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        return (self.next_u64() >> 11) as f64 / ((1u64 << 53) as f64) < probability;
+    }
+
+    pub fn pick<T: Copy>(&mut self, options: &[T]) -> T {
+        return options[self.index(options.len())];
+    }
+
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            let j = self.index(i + 1);
+            values.swap(i, j);
+        }
+    }
+}
+
+// size is the number of lines for line-based puzzles and the side length for grids
+pub fn generate(day: u32, seed: u64, size: usize) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    let size = std::cmp::max(size, 1);
+
+    return match day {
+        1 => generate_day_1(&mut rng, size),
+        2 => generate_day_2(&mut rng, size),
+        3 => generate_day_3(&mut rng, size),
+        4 => generate_day_4(&mut rng, size),
+        5 => generate_day_5(&mut rng, size),
+        6 => generate_day_6(&mut rng, size),
+        7 => generate_day_7(&mut rng, size),
+        8 => generate_day_8(&mut rng, size),
+        9 => generate_day_9(&mut rng, size),
+        10 => generate_day_10(&mut rng, size),
+        11 => generate_day_11(&mut rng, size),
+        12 => generate_day_12(&mut rng, size),
+        13 => generate_day_13(&mut rng, size),
+        14 => generate_day_14(&mut rng, size),
+        15 => generate_day_15(&mut rng, size),
+        16 => generate_day_16(&mut rng, size),
+        17 => generate_day_17(&mut rng, size),
+        _ => panic!("no generator for day {}", day),
+    };
+}
+
+pub fn get_size_warning(day: u32, size: usize) -> Option<String> {
+    let size = std::cmp::max(size, 1);
+    return match day {
+        5 if get_almanac_max_value(size) < ALMANAC_MAX_VALUE => Some(format!(
+            "day 5 values stay below {} at size {}, so that seeds can be checked one by one",
+            get_almanac_max_value(size),
+            size
+        )),
+        6 if size > MAX_DAY_6_RACES => Some(format!(
+            "day 6 has at most {} races, so size {} is treated as {}",
+            MAX_DAY_6_RACES, size, MAX_DAY_6_RACES
+        )),
+        12 if get_max_record_length(size) < MAX_RECORD_LENGTH => Some(format!(
+            "day 12 records are at most {} long at size {}, so that unfolded records can be \
+             checked one by one",
+            get_max_record_length(size),
+            size
+        )),
+        _ => None,
+    };
+}
+
+const DIGIT_WORDS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+fn generate_day_1(rng: &mut Rng, size: usize) -> Vec<String> {
+    return (0..size)
+        .map(|_| {
+            let mut line = String::new();
+            let num_tokens = rng.range(1, 8);
+            for _ in 0..num_tokens {
+                match rng.range(0, 3) {
+                    0 => line.push((b'0' + rng.range(0, 10) as u8) as char),
+                    1 => line += rng.pick(&DIGIT_WORDS),
+                    _ => {
+                        for _ in 0..rng.range(1, 5) {
+                            line.push((b'a' + rng.range(0, 26) as u8) as char);
+                        }
+                    }
+                }
+            }
+
+            // step 1 needs at least one numeric digit on every line
+            let digit = (b'1' + rng.range(0, 9) as u8) as char;
+            let insert_index = rng.index(line.len() + 1);
+            line.insert(insert_index, digit);
+            return line;
+        })
+        .collect();
+}
+
+fn generate_day_2(rng: &mut Rng, size: usize) -> Vec<String> {
+    return (0..size)
+        .map(|game_index| {
+            let draws: Vec<String> = (0..rng.range(1, 7))
+                .map(|_| {
+                    let mut colors = ["red", "green", "blue"];
+                    rng.shuffle(&mut colors);
+                    let num_colors = rng.range(1, 4) as usize;
+                    return colors[..num_colors]
+                        .iter()
+                        .map(|color| format!("{} {}", rng.range(1, 21), color))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                })
+                .collect();
+            return format!("Game {}: {}", game_index + 1, draws.join("; "));
+        })
+        .collect();
+}
+
+const SCHEMATIC_SYMBOLS: [u8; 10] = [b'*', b'#', b'+', b'$', b'/', b'@', b'=', b'%', b'&', b'-'];
+
+fn generate_day_3(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut grid: Vec<Vec<u8>> = vec![vec![b'.'; size]; size];
+    for row in grid.iter_mut() {
+        let mut column = 0;
+        while column < size {
+            if rng.chance(0.15) {
+                let length = std::cmp::min(rng.range(1, 4) as usize, size - column);
+                for offset in 0..length {
+                    let low = if offset == 0 { 1 } else { 0 };
+                    row[column + offset] = b'0' + rng.range(low, 10) as u8;
+                }
+                // leave a gap so that neighbouring numbers don't merge
+                column += length + 1;
+            } else {
+                if rng.chance(0.05) {
+                    row[column] = rng.pick(&SCHEMATIC_SYMBOLS);
+                }
+                column += 1;
+            }
+        }
+    }
+
+    return grid_to_lines(grid);
+}
+
+fn generate_day_4(rng: &mut Rng, size: usize) -> Vec<String> {
+    return (0..size)
+        .map(|card_index| {
+            let mut numbers: Vec<u64> = (1..100).collect();
+            rng.shuffle(&mut numbers);
+            let winning_numbers = &numbers[..10];
+
+            // the puzzle promises that cards never win copies past the end of the table
+            let max_matches = std::cmp::min(10, size - card_index - 1);
+            let num_matches = rng.range(0, max_matches as u64 + 1) as usize;
+            let mut held_numbers: Vec<u64> = winning_numbers[..num_matches].to_vec();
+            held_numbers.extend(&numbers[10..(35 - num_matches)]);
+            rng.shuffle(&mut held_numbers);
+
+            let format_numbers = |values: &[u64]| {
+                values
+                    .iter()
+                    .map(|value| format!("{:>2}", value))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            };
+
+            return format!(
+                "Card {:>3}: {} | {}",
+                card_index + 1,
+                format_numbers(winning_numbers),
+                format_numbers(&held_numbers)
+            );
+        })
+        .collect();
+}
+
+const ALMANAC_CATEGORIES: [&str; 8] = [
+    "seed",
+    "soil",
+    "fertilizer",
+    "water",
+    "light",
+    "temperature",
+    "humidity",
+    "location",
+];
+
+const ALMANAC_MAX_VALUE: u64 = 1 << 32;
+
+// small almanacs get a small value space, so that seeds can be checked one by one
+fn get_almanac_max_value(size: usize) -> u64 {
+    return std::cmp::min(
+        ALMANAC_MAX_VALUE,
+        (size as u64).saturating_pow(3).saturating_mul(16),
+    );
+}
+
+fn generate_day_5(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let max_value = get_almanac_max_value(size);
+
+    let seeds: Vec<String> = (0..std::cmp::max(size / 5, 1) * 2)
+        .map(|i| {
+            if i % 2 == 0 {
+                rng.range(0, max_value / 2).to_string()
+            } else {
+                rng.range(1, max_value / 16).to_string()
+            }
+        })
+        .collect();
+    lines.push(format!("seeds: {}", seeds.join(" ")));
+
+    for pair in ALMANAC_CATEGORIES.windows(2) {
+        lines.push(String::new());
+        lines.push(format!("{}-to-{} map:", pair[0], pair[1]));
+
+        // cut [0, max) into non-overlapping source ranges, then lay the same lengths out in a
+        // shuffled order on the destination side so that the mapping is a bijection.
+        let mut cut_points: Vec<u64> = (0..size).map(|_| rng.range(1, max_value)).collect();
+        cut_points.push(0);
+        cut_points.push(max_value);
+        cut_points.sort_unstable();
+        cut_points.dedup();
+
+        let sources: Vec<(u64, u64)> = cut_points
+            .windows(2)
+            .map(|window| (window[0], window[1] - window[0]))
+            .collect();
+        let mut destination_order: Vec<usize> = (0..sources.len()).collect();
+        rng.shuffle(&mut destination_order);
+
+        let mut destination_starts: Vec<u64> = vec![0; sources.len()];
+        let mut next_destination = 0;
+        for source_index in destination_order {
+            destination_starts[source_index] = next_destination;
+            next_destination += sources[source_index].1;
+        }
+
+        let mut mappings: Vec<String> = sources
+            .iter()
+            .zip(destination_starts)
+            // drop some ranges entirely to exercise the identity fallback
+            .filter(|_| rng.chance(0.8))
+            .map(|((source_start, length), destination_start)| {
+                format!("{} {} {}", destination_start, source_start, length)
+            })
+            .collect();
+        rng.shuffle(&mut mappings);
+        lines.extend(mappings);
+    }
+
+    return lines;
+}
+
+// step 2 joins every race into one, which has to fit in an i64 and be winnable, and the oracle
+// tries every hold time for it
+const MAX_DAY_6_RACES: usize = 3;
+
+fn join_digits(values: &[u64]) -> u64 {
+    return values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<String>()
+        .parse::<u64>()
+        .unwrap();
+}
+
+fn generate_day_6(rng: &mut Rng, size: usize) -> Vec<String> {
+    let num_races = std::cmp::min(size, MAX_DAY_6_RACES);
+    let (times, distances) = loop {
+        let mut times: Vec<u64> = Vec::new();
+        let mut distances: Vec<u64> = Vec::new();
+        for _ in 0..num_races {
+            let time = rng.range(2, 100);
+            let best_distance = (time / 2) * (time - time / 2);
+            times.push(time);
+            distances.push(rng.range(0, best_distance));
+        }
+
+        // every race can be won on its own, but the joined race might not be
+        let time = join_digits(&times);
+        if join_digits(&distances) < (time / 2) * (time - time / 2) {
+            break (times, distances);
+        }
+    };
+
+    let format_numbers = |values: &[u64]| {
+        values
+            .iter()
+            .map(|value| format!("{:>5}", value))
+            .collect::<String>()
+    };
+
+    return vec![
+        format!("Time:    {}", format_numbers(&times)),
+        format!("Distance:{}", format_numbers(&distances)),
+    ];
+}
+
+const CARDS: [u8; 13] = [
+    b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b'T', b'J', b'Q', b'K', b'A',
+];
+
+fn generate_day_7(rng: &mut Rng, size: usize) -> Vec<String> {
+    return (0..size)
+        .map(|_| {
+            let hand: String = (0..5).map(|_| rng.pick(&CARDS) as char).collect();
+            return format!("{} {}", hand, rng.range(1, 1001));
+        })
+        .collect();
+}
+
+fn generate_day_8(rng: &mut Rng, size: usize) -> Vec<String> {
+    let instructions: String = (0..std::cmp::max(size / 2, 1))
+        .map(|_| if rng.chance(0.5) { 'L' } else { 'R' })
+        .collect();
+
+    // pick distinct node names, keeping AAA first and ZZZ last so that step 1 has a path
+    let num_nodes = std::cmp::max(size, 2);
+    let mut names: HashSet<String> = HashSet::new();
+    names.insert("AAA".to_owned());
+    names.insert("ZZZ".to_owned());
+    let mut middle_names: Vec<String> = Vec::new();
+    while middle_names.len() < num_nodes - 2 && names.len() < 26 * 26 * 26 {
+        let name: String = (0..3)
+            .map(|_| (b'A' + rng.range(0, 26) as u8) as char)
+            .collect();
+        if names.insert(name.clone()) {
+            middle_names.push(name);
+        }
+    }
+
+    let mut ordered_names: Vec<String> = vec!["AAA".to_owned()];
+    ordered_names.extend(middle_names);
+    ordered_names.push("ZZZ".to_owned());
+
+    // every node only points forwards, and ZZZ points to itself, so every walk ends at ZZZ
+    let last = ordered_names.len() - 1;
+    let mut nodes: Vec<String> = ordered_names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let left = std::cmp::min(last, index + rng.range(1, 3) as usize);
+            let right = std::cmp::min(last, index + rng.range(1, 3) as usize);
+            return format!(
+                "{} = ({}, {})",
+                name, ordered_names[left], ordered_names[right]
+            );
+        })
+        .collect();
+    rng.shuffle(&mut nodes);
+
+    let mut lines = vec![instructions, String::new()];
+    lines.extend(nodes);
+    return lines;
+}
+
+fn generate_day_9(rng: &mut Rng, size: usize) -> Vec<String> {
+    return (0..size)
+        .map(|_| {
+            let degree = rng.range(0, 6);
+            let coefficients: Vec<i64> =
+                (0..=degree).map(|_| rng.range(0, 11) as i64 - 5).collect();
+            return (0..21)
+                .map(|x: i64| {
+                    coefficients
+                        .iter()
+                        .rev()
+                        .fold(0, |total, coefficient| total * x + coefficient)
+                        .to_string()
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+        })
+        .collect();
+}
+
+const PIPES: [u8; 7] = [b'.', b'|', b'-', b'L', b'J', b'7', b'F'];
+
+const DAY_10_NORTH: u8 = 1;
+const DAY_10_SOUTH: u8 = 2;
+const DAY_10_EAST: u8 = 4;
+const DAY_10_WEST: u8 = 8;
+
+fn pipe_for_directions(directions: u8) -> u8 {
+    return match directions {
+        d if d == DAY_10_EAST | DAY_10_WEST => b'-',
+        d if d == DAY_10_NORTH | DAY_10_SOUTH => b'|',
+        d if d == DAY_10_NORTH | DAY_10_EAST => b'L',
+        d if d == DAY_10_NORTH | DAY_10_WEST => b'J',
+        d if d == DAY_10_SOUTH | DAY_10_EAST => b'F',
+        d if d == DAY_10_SOUTH | DAY_10_WEST => b'7',
+        _ => panic!("not a pipe"),
+    };
+}
+
+fn generate_day_10(rng: &mut Rng, size: usize) -> Vec<String> {
+    // the loop is traced around a random spanning tree of coarse blocks. each block is
+    // BLOCK x BLOCK tiles with the loop running along its border, and tree edges open the
+    // border between two blocks, leaving the inside of the blocks enclosed.
+    const BLOCK: usize = 3;
+    let coarse_size = std::cmp::max((std::cmp::max(size, 2) - 2) / BLOCK, 1);
+    let side = coarse_size * BLOCK + 2;
+
+    let mut in_tree = vec![vec![false; coarse_size]; coarse_size];
+    // bit flags per coarse cell of which neighbours it's joined to
+    let mut joins = vec![vec![0u8; coarse_size]; coarse_size];
+
+    let start = (rng.index(coarse_size), rng.index(coarse_size));
+    in_tree[start.0][start.1] = true;
+    let mut stack = vec![start];
+    let target_cells = std::cmp::max(coarse_size * coarse_size * 2 / 3, 1);
+    let mut num_cells = 1;
+    while let Some(&(row, column)) = stack.last() {
+        if num_cells >= target_cells {
+            break;
+        }
+        let mut options: Vec<(usize, usize, u8)> = Vec::new();
+        if row > 0 && !in_tree[row - 1][column] {
+            options.push((row - 1, column, DAY_10_NORTH));
+        }
+        if row + 1 < coarse_size && !in_tree[row + 1][column] {
+            options.push((row + 1, column, DAY_10_SOUTH));
+        }
+        if column > 0 && !in_tree[row][column - 1] {
+            options.push((row, column - 1, DAY_10_WEST));
+        }
+        if column + 1 < coarse_size && !in_tree[row][column + 1] {
+            options.push((row, column + 1, DAY_10_EAST));
+        }
+        if options.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        let (new_row, new_column, direction) = rng.pick(&options);
+        joins[row][column] |= direction;
+        joins[new_row][new_column] |= match direction {
+            DAY_10_NORTH => DAY_10_SOUTH,
+            DAY_10_SOUTH => DAY_10_NORTH,
+            DAY_10_EAST => DAY_10_WEST,
+            _ => DAY_10_EAST,
+        };
+        in_tree[new_row][new_column] = true;
+        num_cells += 1;
+        stack.push((new_row, new_column));
+    }
+
+    let mut grid: Vec<Vec<u8>> = (0..side)
+        .map(|_| (0..side).map(|_| rng.pick(&PIPES)).collect())
+        .collect();
+    let mut loop_tiles: Vec<(usize, usize)> = Vec::new();
+
+    for row in 0..coarse_size {
+        for column in 0..coarse_size {
+            if !in_tree[row][column] {
+                continue;
+            }
+            let joined = joins[row][column];
+            let has = |direction: u8| joined & direction != 0;
+
+            for row_offset in 0..BLOCK {
+                for column_offset in 0..BLOCK {
+                    let is_top = row_offset == 0;
+                    let is_bottom = row_offset == BLOCK - 1;
+                    let is_left = column_offset == 0;
+                    let is_right = column_offset == BLOCK - 1;
+
+                    let directions = match (is_top, is_bottom, is_left, is_right) {
+                        (true, _, true, _) => {
+                            (if has(DAY_10_NORTH) {
+                                DAY_10_NORTH
+                            } else {
+                                DAY_10_EAST
+                            }) | (if has(DAY_10_WEST) {
+                                DAY_10_WEST
+                            } else {
+                                DAY_10_SOUTH
+                            })
+                        }
+                        (true, _, _, true) => {
+                            (if has(DAY_10_NORTH) {
+                                DAY_10_NORTH
+                            } else {
+                                DAY_10_WEST
+                            }) | (if has(DAY_10_EAST) {
+                                DAY_10_EAST
+                            } else {
+                                DAY_10_SOUTH
+                            })
+                        }
+                        (_, true, true, _) => {
+                            (if has(DAY_10_SOUTH) {
+                                DAY_10_SOUTH
+                            } else {
+                                DAY_10_EAST
+                            }) | (if has(DAY_10_WEST) {
+                                DAY_10_WEST
+                            } else {
+                                DAY_10_NORTH
+                            })
+                        }
+                        (_, true, _, true) => {
+                            (if has(DAY_10_SOUTH) {
+                                DAY_10_SOUTH
+                            } else {
+                                DAY_10_WEST
+                            }) | (if has(DAY_10_EAST) {
+                                DAY_10_EAST
+                            } else {
+                                DAY_10_NORTH
+                            })
+                        }
+                        (true, _, _, _) if !has(DAY_10_NORTH) => DAY_10_EAST | DAY_10_WEST,
+                        (_, true, _, _) if !has(DAY_10_SOUTH) => DAY_10_EAST | DAY_10_WEST,
+                        (_, _, true, _) if !has(DAY_10_WEST) => DAY_10_NORTH | DAY_10_SOUTH,
+                        (_, _, _, true) if !has(DAY_10_EAST) => DAY_10_NORTH | DAY_10_SOUTH,
+                        _ => 0,
+                    };
+
+                    if directions != 0 {
+                        let line_index = row * BLOCK + 1 + row_offset;
+                        let byte_index = column * BLOCK + 1 + column_offset;
+                        grid[line_index][byte_index] = pipe_for_directions(directions);
+                        loop_tiles.push((line_index, byte_index));
+                    }
+                }
+            }
+        }
+    }
+
+    // a lone block is its own loop; anything bigger has at least four tiles either way
+    let (start_line, start_byte) = rng.pick(&loop_tiles);
+    grid[start_line][start_byte] = b'S';
+
+    // make sure that only the two loop neighbours of S appear to connect to it
+    let neighbours = [
+        (start_line - 1, start_byte, DAY_10_SOUTH),
+        (start_line + 1, start_byte, DAY_10_NORTH),
+        (start_line, start_byte - 1, DAY_10_EAST),
+        (start_line, start_byte + 1, DAY_10_WEST),
+    ];
+    for (line_index, byte_index, direction_to_start) in neighbours {
+        if loop_tiles.contains(&(line_index, byte_index)) {
+            continue;
+        }
+        let pipe = grid[line_index][byte_index];
+        if pipe != b'.' && pipe_directions(pipe) & direction_to_start != 0 {
+            grid[line_index][byte_index] = b'.';
+        }
+    }
+
+    return grid_to_lines(grid);
+}
+
+fn pipe_directions(pipe: u8) -> u8 {
+    return match pipe {
+        b'-' => DAY_10_EAST | DAY_10_WEST,
+        b'|' => DAY_10_NORTH | DAY_10_SOUTH,
+        b'L' => DAY_10_NORTH | DAY_10_EAST,
+        b'J' => DAY_10_NORTH | DAY_10_WEST,
+        b'F' => DAY_10_SOUTH | DAY_10_EAST,
+        b'7' => DAY_10_SOUTH | DAY_10_WEST,
+        _ => 0,
+    };
+}
+
+fn generate_day_11(rng: &mut Rng, size: usize) -> Vec<String> {
+    let empty_rows: Vec<bool> = (0..size).map(|_| rng.chance(0.1)).collect();
+    let empty_columns: Vec<bool> = (0..size).map(|_| rng.chance(0.1)).collect();
+
+    let grid: Vec<Vec<u8>> = (0..size)
+        .map(|row| {
+            (0..size)
+                .map(|column| {
+                    if !empty_rows[row] && !empty_columns[column] && rng.chance(0.05) {
+                        b'#'
+                    } else {
+                        b'.'
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    return grid_to_lines(grid);
+}
+
+const MAX_RECORD_LENGTH: usize = 20;
+
+// the brute-force oracle for unfolded records only copes with short ones, so small inputs get
+// short records
+fn get_max_record_length(size: usize) -> usize {
+    return std::cmp::min(MAX_RECORD_LENGTH, size + 5);
+}
+
+fn generate_day_12(rng: &mut Rng, size: usize) -> Vec<String> {
+    return (0..size)
+        .map(|_| {
+            let length = rng.range(5, get_max_record_length(size) as u64 + 1) as usize;
+            let mut springs: Vec<u8> = (0..length)
+                .map(|_| if rng.chance(0.5) { b'#' } else { b'.' })
+                .collect();
+            if !springs.contains(&b'#') {
+                let index = rng.index(length);
+                springs[index] = b'#';
+            }
+
+            // the groups are derived from the undamaged record, so there's always at least one
+            // valid arrangement
+            let groups: Vec<String> = springs
+                .split(|byte| *byte == b'.')
+                .filter(|group| !group.is_empty())
+                .map(|group| group.len().to_string())
+                .collect();
+
+            let record: String = springs
+                .into_iter()
+                .map(|byte| if rng.chance(0.4) { '?' } else { byte as char })
+                .collect();
+
+            return format!("{} {}", record, groups.join(","));
+        })
+        .collect();
+}
+
+fn generate_day_13(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for pattern_index in 0..size {
+        if pattern_index > 0 {
+            lines.push(String::new());
+        }
+
+        let num_rows = rng.range(5, 18) as usize;
+        let num_columns = rng.range(5, 18) as usize;
+        let mut grid: Vec<Vec<u8>> = (0..num_rows)
+            .map(|_| {
+                (0..num_columns)
+                    .map(|_| if rng.chance(0.5) { b'#' } else { b'.' })
+                    .collect()
+            })
+            .collect();
+
+        // mirror the grid across a random axis so that it has a clean reflection
+        if rng.chance(0.5) {
+            let axis = rng.range(1, num_rows as u64) as usize;
+            for distance in 0..std::cmp::min(axis, num_rows - axis) {
+                grid[axis + distance] = grid[axis - distance - 1].clone();
+            }
+        } else {
+            let axis = rng.range(1, num_columns as u64) as usize;
+            for row in grid.iter_mut() {
+                for distance in 0..std::cmp::min(axis, num_columns - axis) {
+                    row[axis + distance] = row[axis - distance - 1];
+                }
+            }
+        }
+
+        lines.extend(grid_to_lines(grid));
+    }
+
+    return lines;
+}
+
+fn generate_day_14(rng: &mut Rng, size: usize) -> Vec<String> {
+    let grid: Vec<Vec<u8>> = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| match rng.range(0, 10) {
+                    0 | 1 => b'O',
+                    2 => b'#',
+                    _ => b'.',
+                })
+                .collect()
+        })
+        .collect();
+
+    return grid_to_lines(grid);
+}
+
+fn generate_day_15(rng: &mut Rng, size: usize) -> Vec<String> {
+    let labels: Vec<String> = (0..std::cmp::max(size / 4, 1))
+        .map(|_| {
+            (0..rng.range(1, 7))
+                .map(|_| (b'a' + rng.range(0, 26) as u8) as char)
+                .collect()
+        })
+        .collect();
+
+    let steps: Vec<String> = (0..size)
+        .map(|_| {
+            let label = &labels[rng.index(labels.len())];
+            if rng.chance(0.3) {
+                format!("{}-", label)
+            } else {
+                format!("{}={}", label, rng.range(1, 10))
+            }
+        })
+        .collect();
+
+    return vec![steps.join(",")];
+}
+
+fn generate_day_16(rng: &mut Rng, size: usize) -> Vec<String> {
+    let grid: Vec<Vec<u8>> = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| {
+                    if rng.chance(0.12) {
+                        rng.pick(b"/\\|-")
+                    } else {
+                        b'.'
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    return grid_to_lines(grid);
+}
+
+fn generate_day_17(rng: &mut Rng, size: usize) -> Vec<String> {
+    let grid: Vec<Vec<u8>> = (0..size)
+        .map(|_| (0..size).map(|_| b'1' + rng.range(0, 9) as u8).collect())
+        .collect();
+
+    return grid_to_lines(grid);
+}
+
+fn grid_to_lines(grid: Vec<Vec<u8>>) -> Vec<String> {
+    return grid
+        .into_iter()
+        .map(|row| String::from_utf8(row).unwrap())
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_numbers(text: &str) -> Vec<u64> {
+        return text
+            .split_whitespace()
+            .map(|number| number.parse::<u64>().unwrap())
+            .collect();
+    }
+
+    fn has_reflection(grid: &[Vec<u8>]) -> bool {
+        let columns: Vec<Vec<u8>> = (0..grid[0].len())
+            .map(|column| grid.iter().map(|row| row[column]).collect())
+            .collect();
+        return [grid, columns.as_slice()].iter().any(|sequence| {
+            (1..sequence.len()).any(|axis| {
+                (0..std::cmp::min(axis, sequence.len() - axis))
+                    .all(|distance| sequence[axis - distance - 1] == sequence[axis + distance])
+            })
+        });
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_input() {
+        for day in 1..=17 {
+            // act
+            let result = (generate(day, 3, 12), generate(day, 3, 12));
+
+            // assert
+            assert_eq!(result.0, result.1, "day {}", day);
+            assert!(!result.0.is_empty(), "day {}", day);
+        }
+    }
+
+    #[test]
+    fn test_day_1_lines_have_a_digit() {
+        // act
+        let lines = generate(1, 5, 200);
+
+        // assert
+        for line in lines {
+            assert!(line.bytes().any(|byte| byte.is_ascii_digit()), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_day_5_maps_do_not_overlap() {
+        for seed in 0..10 {
+            // act
+            let lines = generate(5, seed, 20);
+
+            // assert
+            let max_value = get_almanac_max_value(20);
+            let seeds = parse_numbers(lines[0].strip_prefix("seeds: ").unwrap());
+            assert!(seeds.iter().all(|value| *value < max_value));
+            for map in lines[2..].split(|line| line.is_empty()) {
+                let mut sources: Vec<(u64, u64)> = Vec::new();
+                let mut destinations: Vec<(u64, u64)> = Vec::new();
+                for line in &map[1..] {
+                    let numbers = parse_numbers(line);
+                    destinations.push((numbers[0], numbers[2]));
+                    sources.push((numbers[1], numbers[2]));
+                }
+                for ranges in [&mut sources, &mut destinations] {
+                    ranges.sort_unstable();
+                    assert!(ranges
+                        .iter()
+                        .all(|(start, length)| start + length <= max_value));
+                    assert!(ranges
+                        .windows(2)
+                        .all(|pair| pair[0].0 + pair[0].1 <= pair[1].0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_day_6_joined_race_can_be_won() {
+        for seed in 0..20 {
+            // act
+            let lines = generate(6, seed, 10);
+
+            // assert
+            let times = parse_numbers(lines[0].strip_prefix("Time:").unwrap());
+            let distances = parse_numbers(lines[1].strip_prefix("Distance:").unwrap());
+            assert_eq!(times.len(), MAX_DAY_6_RACES);
+            let time = join_digits(&times);
+            assert!(join_digits(&distances) < (time / 2) * (time - time / 2));
+        }
+    }
+
+    #[test]
+    fn test_day_8_nodes_lead_to_defined_nodes() {
+        // act
+        let lines = generate(8, 2, 50);
+
+        // assert
+        let nodes: Vec<(&str, &str, &str)> = lines[2..]
+            .iter()
+            .map(|line| (&line[0..3], &line[7..10], &line[12..15]))
+            .collect();
+        let names: HashSet<&str> = nodes.iter().map(|(name, _, _)| *name).collect();
+        assert_eq!(names.len(), nodes.len());
+        assert!(names.contains("AAA") && names.contains("ZZZ"));
+        for (_, left, right) in nodes {
+            assert!(names.contains(left) && names.contains(right));
+        }
+    }
+
+    #[test]
+    fn test_day_10_pipes_form_a_loop_through_the_start() {
+        for seed in 0..10 {
+            // act
+            let grid: Vec<Vec<u8>> = generate(10, seed, 20)
+                .into_iter()
+                .map(|line| line.into_bytes())
+                .collect();
+
+            // assert
+            let start_tiles: Vec<(usize, usize)> = (0..grid.len())
+                .flat_map(|row| (0..grid[row].len()).map(move |column| (row, column)))
+                .filter(|(row, column)| grid[*row][*column] == b'S')
+                .collect();
+            assert_eq!(start_tiles.len(), 1);
+            let start = start_tiles[0];
+
+            let step = |(row, column): (usize, usize), direction: u8| match direction {
+                DAY_10_NORTH => (row - 1, column),
+                DAY_10_SOUTH => (row + 1, column),
+                DAY_10_EAST => (row, column + 1),
+                _ => (row, column - 1),
+            };
+            let opposite = |direction: u8| match direction {
+                DAY_10_NORTH => DAY_10_SOUTH,
+                DAY_10_SOUTH => DAY_10_NORTH,
+                DAY_10_EAST => DAY_10_WEST,
+                _ => DAY_10_EAST,
+            };
+            // the generator never puts the start on the border, so every neighbour exists
+            let start_directions: Vec<u8> = [DAY_10_NORTH, DAY_10_SOUTH, DAY_10_EAST, DAY_10_WEST]
+                .into_iter()
+                .filter(|direction| {
+                    let (row, column) = step(start, *direction);
+                    pipe_directions(grid[row][column]) & opposite(*direction) != 0
+                })
+                .collect();
+            assert_eq!(start_directions.len(), 2);
+
+            let mut position = step(start, start_directions[0]);
+            let mut came_from = opposite(start_directions[0]);
+            let mut length = 1;
+            while position != start {
+                let directions = pipe_directions(grid[position.0][position.1]);
+                assert_ne!(
+                    directions & came_from,
+                    0,
+                    "pipe at {:?} is broken",
+                    position
+                );
+                let direction = directions & !came_from;
+                position = step(position, direction);
+                came_from = opposite(direction);
+                length += 1;
+            }
+            assert_eq!(came_from, start_directions[1]);
+            assert!(length >= 4);
+        }
+    }
+
+    #[test]
+    fn test_day_12_records_have_an_arrangement() {
+        // act
+        let lines = generate(12, 4, 100);
+
+        // assert
+        for line in lines {
+            assert!(crate::day_12::count_for_line(&line, false) > 0, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_day_13_patterns_have_a_reflection() {
+        for seed in 0..10 {
+            // act
+            let lines = generate(13, seed, 10);
+
+            // assert
+            let patterns: Vec<&[String]> = lines.split(|line| line.is_empty()).collect();
+            assert_eq!(patterns.len(), 10);
+            for pattern in patterns {
+                let grid: Vec<Vec<u8>> = pattern
+                    .iter()
+                    .map(|line| line.clone().into_bytes())
+                    .collect();
+                assert!(has_reflection(&grid), "{:?}", pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn test_grids_are_square() {
+        for (day, tiles) in [(14, "O#."), (16, "/\\|-."), (17, "123456789")] {
+            // act
+            let lines = generate(day, 1, 15);
+
+            // assert
+            assert_eq!(lines.len(), 15, "day {}", day);
+            for line in lines {
+                assert_eq!(line.len(), 15, "day {}", day);
+                assert!(line.bytes().all(|byte| tiles.as_bytes().contains(&byte)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_size_warnings() {
+        // act
+        let result = (
+            get_size_warning(5, 10).is_some(),
+            get_size_warning(5, 1000).is_some(),
+            get_size_warning(6, 3).is_some(),
+            get_size_warning(6, 4).is_some(),
+            get_size_warning(12, 14).is_some(),
+            get_size_warning(12, 15).is_some(),
+            get_size_warning(7, 4).is_some(),
+        );
+
+        // assert
+        assert_eq!(result, (true, false, false, true, true, false, false));
+    }
+}
@@ -1,6 +1,7 @@
 use clap::Parser;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{self, BufRead};
+use std::time::{Duration, Instant};
 
 mod day_1;
 mod day_2;
@@ -30,19 +31,374 @@ mod day_23;
 mod day_24;
 mod day_25;
 
+mod examples;
+mod fuzz;
+mod generate;
+mod oracle;
+mod render;
+mod repl;
+mod watch;
+
 #[derive(Parser)]
 struct Args {
     command: String,
-    input_path: String,
+    input_path: Option<String>,
+    #[arg(long)]
+    day: Option<u32>,
+    #[arg(long)]
+    step: Option<u32>,
+    #[arg(long)]
+    watch: bool,
+    #[arg(long)]
+    test: bool,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    #[arg(long, default_value_t = 10)]
+    size: usize,
+    #[arg(long, default_value_t = 100)]
+    count: u64,
+    #[arg(long, default_value_t = 1000)]
+    budget_ms: u64,
+    #[arg(long, default_value = "fuzz-corpus")]
+    corpus: String,
+    #[arg(long)]
+    render: Option<String>,
+    #[arg(long)]
+    dot: Option<String>,
+    #[arg(long, default_value_t = 10)]
+    limit: usize,
+    #[arg(long)]
+    vocabulary: Option<String>,
+    #[arg(long)]
+    stream: bool,
+    #[arg(long)]
+    bag: Option<String>,
+    #[arg(long)]
+    rules: Option<String>,
+    #[arg(long)]
+    json: Option<String>,
+    #[arg(long)]
+    locations: Option<String>,
+    #[arg(long)]
+    cycles: Option<u64>,
+    #[arg(long, default_value_t = 100)]
+    frame_ms: u64,
 }
 
 fn main() {
     let args = Args::parse();
 
-    let file = File::open(args.input_path).unwrap();
+    if args.command == "generate" {
+        let day = args.day.expect("generate requires --day");
+        if let Some(warning) = generate::get_size_warning(day, args.size) {
+            eprintln!("warning: {}", warning);
+        }
+        for line in generate::generate(day, args.seed, args.size) {
+            println!("{}", line);
+        }
+        return;
+    }
+
+    if args.command == "check" {
+        check(
+            args.day.expect("check requires --day"),
+            args.input_path,
+            args.seed,
+            args.size,
+            args.count,
+        );
+        return;
+    }
+
+    if args.command == "fuzz" {
+        let days = match args.day {
+            Some(day) => vec![day],
+            None => (1..=25).collect(),
+        };
+        fuzz::fuzz(
+            &days,
+            args.seed,
+            args.count,
+            Duration::from_millis(args.budget_ms),
+            &args.corpus,
+        );
+        return;
+    }
+
+    if args.command == "repl" {
+        let day = args.day.expect("repl requires --day");
+        let file = File::open(args.input_path.expect("missing input path")).unwrap();
+        let lines = io::BufReader::new(file)
+            .lines()
+            .map(|line| line.unwrap())
+            .collect();
+        repl::repl(day, lines);
+        return;
+    }
+
+    if args.command == "animate" {
+        let day = args.day.expect("animate requires --day");
+        if day != 14 {
+            panic!("only day 14 can be animated");
+        }
+        let file = File::open(args.input_path.expect("missing input path")).unwrap();
+        let lines = io::BufReader::new(file).lines().map(|line| line.unwrap());
+        day_14::animate(lines, Duration::from_millis(args.frame_ms));
+        return;
+    }
+
+    if args.command == "validate" {
+        if args.day != Some(5) {
+            panic!("only day 5 can be validated");
+        }
+        let file = File::open(args.input_path.expect("missing input path")).unwrap();
+        let lines = io::BufReader::new(file).lines().map(|line| line.unwrap());
+        day_5::print_validation(lines);
+        return;
+    }
+
+    if args.command == "show" {
+        let day = args.day.expect("show requires --day");
+        let file = File::open(args.input_path.as_ref().expect("missing input path")).unwrap();
+        let lines = io::BufReader::new(file).lines().map(|line| line.unwrap());
+        show(day, &args, lines);
+        return;
+    }
+
+    if args.command == "run" {
+        let day = args.day.expect("run requires --day");
+        let input_path = args.input_path.expect("missing input path");
+        if args.watch {
+            watch::watch(day, args.step, &input_path, args.test);
+        } else {
+            run(day, args.step, &input_path);
+        }
+        return;
+    }
+
+    if args.command == "bench" {
+        bench(
+            args.day.expect("bench requires --day"),
+            &args.input_path.expect("missing input path"),
+            &args.vocabulary,
+        );
+        return;
+    }
+
+    if args.stream {
+        let scanner = match args.command.as_str() {
+            "day-1-step-1" => day_1::Scanner::digits_only(),
+            "day-1-step-2" => day_1::Scanner::new(&get_vocabulary(&args.vocabulary)),
+            _ => panic!("--stream only applies to day 1"),
+        };
+        let file = File::open(args.input_path.expect("missing input path")).unwrap();
+        println!("sum: {}", scanner.sum_calibration_values(file));
+        return;
+    }
+
+    let file = File::open(args.input_path.expect("missing input path")).unwrap();
+    let lines = io::BufReader::new(file).lines().map(|line| line.unwrap());
+
+    if let Some(bag) = &args.bag {
+        if args.command != "day-2-step-1" {
+            panic!("--bag only applies to day-2-step-1");
+        }
+        day_2::step_1_with_bag(lines, &day_2::parse_bag(bag));
+        return;
+    }
+
+    if args.rules.is_some() || args.json.is_some() {
+        let rules = get_day_3_rules(&args.rules);
+        let schematic = day_3::Schematic::build(lines, &rules);
+        match args.command.as_str() {
+            "day-3-step-1" => println!("sum: {}", schematic.get_part_number_sum()),
+            "day-3-step-2" => println!("sum: {}", schematic.get_gear_score_sum()),
+            _ => panic!("--rules and --json only apply to day 3"),
+        }
+        if let Some(json_path) = args.json {
+            fs::write(json_path, schematic.to_json()).expect("could not write graph");
+        }
+        return;
+    }
+
+    if args.vocabulary.is_some() {
+        if args.command != "day-1-step-2" {
+            panic!("--vocabulary only applies to day-1-step-2");
+        }
+        day_1::step_2_with_vocabulary(lines, &get_vocabulary(&args.vocabulary));
+        return;
+    }
+
+    if args.render.is_none() && args.dot.is_none() {
+        solve(&args.command, lines);
+        return;
+    }
+
+    let (day, step) = parse_day_command(&args.command).expect("unknown command");
+    let lines: Vec<String> = lines.collect();
+    solve(&args.command, lines.clone().into_iter());
+    if let Some(render_path) = args.render {
+        if args.cycles.is_some() && (day, step) != (14, 2) {
+            panic!("--cycles only applies to day-14-step-2");
+        }
+        render::render(day, step, lines.clone(), args.cycles).write(&render_path);
+    }
+    if let Some(dot_path) = args.dot {
+        if day != 8 {
+            panic!("only day 8 can be exported as a graph");
+        }
+        fs::write(dot_path, day_8::to_dot(lines)).expect("could not write graph");
+    }
+}
+
+fn get_day_3_rules(rules: &Option<String>) -> day_3::Rules {
+    let Some(rules) = rules else {
+        return day_3::Rules::default();
+    };
+    return match day_3::Rules::parse(rules) {
+        Ok(rules) => rules,
+        Err(message) => {
+            eprintln!("bad --rules: {}", message);
+            std::process::exit(1);
+        }
+    };
+}
+
+fn get_vocabulary(name: &Option<String>) -> day_1::Vocabulary {
+    return match name {
+        None => day_1::Vocabulary::english(),
+        Some(name) => {
+            day_1::Vocabulary::built_in(name).unwrap_or_else(|| day_1::Vocabulary::from_file(name))
+        }
+    };
+}
+
+fn bench(day: u32, input_path: &str, vocabulary_name: &Option<String>) {
+    if day != 1 {
+        panic!("only day 1 has a benchmark");
+    }
+    let vocabulary = get_vocabulary(vocabulary_name);
+    let num_bytes = fs::metadata(input_path).unwrap().len();
+    let print_timing = |name: &str, sum: u64, elapsed: Duration| {
+        println!(
+            "{}: sum {}, took {:?} ({:.1} MB/s)",
+            name,
+            sum,
+            elapsed,
+            num_bytes as f64 / 1e6 / elapsed.as_secs_f64()
+        );
+    };
+
+    let start = Instant::now();
+    let file = File::open(input_path).unwrap();
     let lines = io::BufReader::new(file).lines().map(|line| line.unwrap());
+    let regex_sum = day_1::solve_step_2_with_vocabulary(lines, &vocabulary);
+    print_timing("regex", regex_sum, start.elapsed());
+
+    let start = Instant::now();
+    let scanner = day_1::Scanner::new(&vocabulary);
+    let file = File::open(input_path).unwrap();
+    let scanner_sum = scanner.sum_calibration_values(file);
+    print_timing("scanner", scanner_sum, start.elapsed());
+
+    if regex_sum != scanner_sum {
+        println!("the sums are different");
+    }
+}
+
+fn show(day: u32, args: &Args, lines: impl Iterator<Item = String>) {
+    let step = args.step.unwrap_or(1);
+    match day {
+        2 if step == 2 => day_2::print_bag_estimates(lines),
+        2 => day_2::print_games(
+            lines,
+            &day_2::parse_bag(args.bag.as_deref().unwrap_or(day_2::DEFAULT_BAG)),
+        ),
+        3 => print!("{}", day_3::to_html(lines, &get_day_3_rules(&args.rules))),
+        4 => day_4::print_report(lines),
+        5 => match &args.locations {
+            Some(locations) => day_5::print_inverse(lines, locations),
+            None => day_5::print_composed(lines),
+        },
+        10 => day_10::pretty_print(lines),
+        12 => day_12::print_arrangements(lines, step, args.limit),
+        13 => day_13::print_reflections(lines, step),
+        16 => day_16::print_heatmap(lines, step),
+        17 => day_17::print_route(lines, step),
+        _ => panic!("day {} has nothing to show", day),
+    }
+}
+
+fn parse_day_command(command: &str) -> Option<(u32, u32)> {
+    let parts: Vec<&str> = command.split('-').collect();
+    return match parts.as_slice() {
+        ["day", day, "step", step] => Some((day.parse().ok()?, step.parse().ok()?)),
+        _ => None,
+    };
+}
+
+fn run(day: u32, step: Option<u32>, input_path: &str) {
+    let steps = match step {
+        Some(step) => vec![step],
+        None => vec![1, 2],
+    };
+
+    for step in steps {
+        let file = File::open(input_path).unwrap();
+        let lines = io::BufReader::new(file).lines().map(|line| line.unwrap());
+
+        println!("day {} step {}:", day, step);
+        let start = Instant::now();
+        solve(&format!("day-{}-step-{}", day, step), lines);
+        println!("took {:?}", start.elapsed());
+    }
+}
+
+fn check(day: u32, input_path: Option<String>, seed: u64, size: usize, count: u64) {
+    if oracle::get_oracles(day).is_empty() {
+        panic!("no reference implementation for day {}", day);
+    }
+
+    let result = match input_path {
+        Some(path) => {
+            let file = File::open(path).unwrap();
+            let lines: Vec<String> = io::BufReader::new(file)
+                .lines()
+                .map(|line| line.unwrap())
+                .collect();
+            oracle::compare(day, &lines).map(|disagreement| (lines, disagreement))
+        }
+        None => {
+            if let Some(warning) = generate::get_size_warning(day, size) {
+                eprintln!("warning: {}", warning);
+            }
+            oracle::run_differential(day, seed, size, count)
+        }
+    };
+
+    match result {
+        Some((lines, disagreement)) => {
+            let format_outcome = |outcome: &oracle::Outcome| match outcome {
+                Ok(value) => value.to_string(),
+                Err(message) => format!("a panic ({})", message),
+            };
+            println!(
+                "step {} disagrees: solver gave {}, reference gave {}",
+                disagreement.step,
+                format_outcome(&disagreement.solver),
+                format_outcome(&disagreement.oracle)
+            );
+            println!("input:");
+            for line in lines {
+                println!("{}", line);
+            }
+        }
+        None => println!("no disagreements"),
+    }
+}
 
-    match args.command.as_str() {
+fn solve(command: &str, lines: impl Iterator<Item = String>) {
+    match command {
         "day-1-step-1" => day_1::step_1(lines),
         "day-1-step-2" => day_1::step_2(lines),
         "day-2-step-1" => day_2::step_1(lines),
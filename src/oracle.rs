@@ -0,0 +1,400 @@
+use crate::{day_11, day_12, day_13, day_5, day_6, examples, generate};
+use std::panic;
+use std::vec::Vec;
+
+// naive reference implementations for the days whose solutions rely on a shortcut. these are
+// deliberately slow and only meant for small inputs.
+
+fn parse_numbers(line: &str) -> Vec<i64> {
+    return line
+        .split(|character: char| !character.is_ascii_digit())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse::<i64>().unwrap())
+        .collect();
+}
+
+fn parse_almanac(lines: &[String]) -> (Vec<i64>, Vec<Vec<Vec<i64>>>) {
+    let seed_numbers = parse_numbers(&lines[0]);
+
+    let mut sections: Vec<Vec<Vec<i64>>> = Vec::new();
+    for line in &lines[1..] {
+        if line.ends_with("map:") {
+            sections.push(Vec::new());
+        } else if !line.is_empty() {
+            sections.last_mut().unwrap().push(parse_numbers(line));
+        }
+    }
+
+    return (seed_numbers, sections);
+}
+
+fn get_location(seed: i64, sections: &[Vec<Vec<i64>>]) -> i64 {
+    let mut value = seed;
+    for section in sections {
+        for mapping in section {
+            if value >= mapping[1] && value < mapping[1] + mapping[2] {
+                value = value - mapping[1] + mapping[0];
+                break;
+            }
+        }
+    }
+    return value;
+}
+
+fn day_5_step_1(lines: Vec<String>) -> i64 {
+    let (seed_numbers, sections) = parse_almanac(&lines);
+    return seed_numbers
+        .into_iter()
+        .map(|seed| get_location(seed, &sections))
+        .min()
+        .unwrap();
+}
+
+fn day_5_step_2(lines: Vec<String>) -> i64 {
+    let (seed_numbers, sections) = parse_almanac(&lines);
+
+    let mut lowest = i64::MAX;
+    for pair in seed_numbers.chunks(2) {
+        for seed in pair[0]..(pair[0] + pair[1]) {
+            lowest = std::cmp::min(lowest, get_location(seed, &sections));
+        }
+    }
+
+    return lowest;
+}
+
+fn count_winning_hold_times(time: i64, distance: i64) -> i64 {
+    return (0..=time)
+        .filter(|hold_time| hold_time * (time - hold_time) > distance)
+        .count() as i64;
+}
+
+fn day_6_step_1(lines: Vec<String>) -> i64 {
+    let times = parse_numbers(&lines[0]);
+    let distances = parse_numbers(&lines[1]);
+    return times
+        .into_iter()
+        .zip(distances)
+        .map(|(time, distance)| count_winning_hold_times(time, distance))
+        .product();
+}
+
+fn day_6_step_2(lines: Vec<String>) -> i64 {
+    let join_digits = |line: &str| {
+        line.bytes()
+            .filter(|byte| byte.is_ascii_digit())
+            .map(|byte| byte as char)
+            .collect::<String>()
+            .parse::<i64>()
+            .unwrap()
+    };
+    return count_winning_hold_times(join_digits(&lines[0]), join_digits(&lines[1]));
+}
+
+fn day_11_sum_of_distances(lines: Vec<String>, expansion_factor: i64) -> i64 {
+    let grid: Vec<&[u8]> = lines.iter().map(|line| line.as_bytes()).collect();
+    let is_empty_row: Vec<bool> = grid.iter().map(|row| !row.contains(&b'#')).collect();
+    let is_empty_column: Vec<bool> = (0..grid[0].len())
+        .map(|column| grid.iter().all(|row| row[column] != b'#'))
+        .collect();
+
+    let mut galaxies: Vec<(usize, usize)> = Vec::new();
+    for (row_index, row) in grid.iter().enumerate() {
+        for (column_index, byte) in row.iter().enumerate() {
+            if *byte == b'#' {
+                galaxies.push((row_index, column_index));
+            }
+        }
+    }
+
+    // walk from one galaxy to the other a step at a time, paying extra for every empty line
+    let step_cost = |is_empty: bool| if is_empty { expansion_factor } else { 1 };
+    let mut total = 0;
+    for (index, a) in galaxies.iter().enumerate() {
+        for b in &galaxies[(index + 1)..] {
+            let rows = &is_empty_row[std::cmp::min(a.0, b.0)..std::cmp::max(a.0, b.0)];
+            let columns = &is_empty_column[std::cmp::min(a.1, b.1)..std::cmp::max(a.1, b.1)];
+            for is_empty in rows.iter().chain(columns) {
+                total += step_cost(*is_empty);
+            }
+        }
+    }
+
+    return total;
+}
+
+fn get_groups(springs: &[u8]) -> Vec<usize> {
+    return springs
+        .split(|byte| *byte != b'#')
+        .filter(|group| !group.is_empty())
+        .map(|group| group.len())
+        .collect();
+}
+
+fn parse_record(line: &str) -> (Vec<u8>, Vec<usize>) {
+    let (record, groups_str) = line.split_once(' ').unwrap();
+    let groups: Vec<usize> = groups_str
+        .split(',')
+        .map(|group| group.parse::<usize>().unwrap())
+        .collect();
+    return (record.as_bytes().to_vec(), groups);
+}
+
+fn day_12_step_1(lines: Vec<String>) -> i64 {
+    let mut total = 0;
+    for line in lines {
+        let (mut springs, groups) = parse_record(&line);
+        let unknown_indices: Vec<usize> = springs
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == b'?')
+            .map(|(index, _)| index)
+            .collect();
+
+        // try every assignment of the unknown springs
+        for assignment in 0u64..(1 << unknown_indices.len()) {
+            for (bit, index) in unknown_indices.iter().enumerate() {
+                springs[*index] = if assignment & (1 << bit) != 0 {
+                    b'#'
+                } else {
+                    b'.'
+                };
+            }
+            if get_groups(&springs) == groups {
+                total += 1;
+            }
+        }
+    }
+
+    return total;
+}
+
+// walks the record from the left, trying both states for every unknown spring, so it visits each
+// arrangement on its own. `run` is the length of the group of damaged springs being walked through.
+fn count_arrangements(springs: &[u8], groups: &[usize], run: usize) -> i64 {
+    let Some((byte, rest)) = springs.split_first() else {
+        return match groups {
+            [] => 1,
+            [group] if *group == run => 1,
+            _ => 0,
+        };
+    };
+
+    let mut total = 0;
+    if *byte != b'.' && groups.first().is_some_and(|group| run < *group) {
+        total += count_arrangements(rest, groups, run + 1);
+    }
+    if *byte != b'#' {
+        if run == 0 {
+            total += count_arrangements(rest, groups, 0);
+        } else if run == groups[0] {
+            total += count_arrangements(rest, &groups[1..], 0);
+        }
+    }
+    return total;
+}
+
+fn day_12_step_2(lines: Vec<String>) -> i64 {
+    let mut total = 0;
+    for line in lines {
+        let (springs, groups) = parse_record(&line);
+        let mut unfolded_springs = springs.clone();
+        for _ in 0..4 {
+            unfolded_springs.push(b'?');
+            unfolded_springs.extend(&springs);
+        }
+        total += count_arrangements(&unfolded_springs, &groups.repeat(5), 0);
+    }
+
+    return total;
+}
+
+// counted in rows above the axis, or 0 if there is none
+fn find_reflection(grid: &[Vec<u8>], target_mismatches: usize) -> usize {
+    for axis in 1..grid.len() {
+        let mut mismatches = 0;
+        for distance in 0..std::cmp::min(axis, grid.len() - axis) {
+            let low = &grid[axis - distance - 1];
+            let high = &grid[axis + distance];
+            mismatches += low.iter().zip(high).filter(|(a, b)| a != b).count();
+        }
+        if mismatches == target_mismatches {
+            return axis;
+        }
+    }
+
+    return 0;
+}
+
+fn day_13_total(lines: Vec<String>, target_mismatches: usize) -> i64 {
+    let mut total = 0;
+    for pattern in lines.split(|line| line.is_empty()) {
+        let rows: Vec<Vec<u8>> = pattern
+            .iter()
+            .map(|line| line.as_bytes().to_vec())
+            .collect();
+        let columns: Vec<Vec<u8>> = (0..rows[0].len())
+            .map(|column| rows.iter().map(|row| row[column]).collect())
+            .collect();
+        total += 100 * find_reflection(&rows, target_mismatches)
+            + find_reflection(&columns, target_mismatches);
+    }
+
+    return total as i64;
+}
+
+type Solver = fn(Vec<String>) -> i64;
+
+pub fn get_oracles(day: u32) -> Vec<(u32, Solver, Solver)> {
+    return match day {
+        5 => vec![
+            (1, |lines| day_5::solve_step_1(lines), day_5_step_1),
+            (1, |lines| day_5::solve_composed(lines).0, day_5_step_1),
+            (2, |lines| day_5::solve_step_2(lines), day_5_step_2),
+            (2, |lines| day_5::solve_composed(lines).1, day_5_step_2),
+            (2, |lines| day_5::solve_step_2_inverse(lines), day_5_step_2),
+        ],
+        6 => vec![
+            (1, |lines| day_6::solve_step_1(lines), day_6_step_1),
+            (2, |lines| day_6::solve_step_2(lines), day_6_step_2),
+        ],
+        11 => vec![
+            (
+                1,
+                |lines| day_11::solve_step_1(lines) as i64,
+                |lines| day_11_sum_of_distances(lines, 2),
+            ),
+            (
+                2,
+                |lines| day_11::solve_step_2(lines) as i64,
+                |lines| day_11_sum_of_distances(lines, 1000000),
+            ),
+        ],
+        12 => vec![
+            (1, |lines| day_12::solve_step_1(lines) as i64, day_12_step_1),
+            (2, |lines| day_12::solve_step_2(lines) as i64, day_12_step_2),
+        ],
+        13 => vec![
+            (
+                1,
+                |lines| day_13::solve_step_1(lines) as i64,
+                |lines| day_13_total(lines, 0),
+            ),
+            (
+                2,
+                |lines| day_13::solve_step_2(lines) as i64,
+                |lines| day_13_total(lines, 1),
+            ),
+        ],
+        _ => Vec::new(),
+    };
+}
+
+pub type Outcome = Result<i64, String>;
+
+#[derive(Debug, PartialEq)]
+pub struct Disagreement {
+    pub step: u32,
+    pub solver: Outcome,
+    pub oracle: Outcome,
+}
+
+fn run_catching_panics(solver: Solver, lines: &[String]) -> Outcome {
+    let lines = lines.to_vec();
+    return panic::catch_unwind(move || solver(lines)).map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            return message.to_string();
+        }
+        if let Some(message) = payload.downcast_ref::<String>() {
+            return message.clone();
+        }
+        return "panicked".to_owned();
+    });
+}
+
+// a panic on either side counts as a disagreement, even if both panic
+pub fn compare(day: u32, lines: &[String]) -> Option<Disagreement> {
+    for (step, solver, oracle) in get_oracles(day) {
+        let solver_result = run_catching_panics(solver, lines);
+        let oracle_result = run_catching_panics(oracle, lines);
+        if solver_result.is_err() || oracle_result.is_err() || solver_result != oracle_result {
+            return Some(Disagreement {
+                step,
+                solver: solver_result,
+                oracle: oracle_result,
+            });
+        }
+    }
+
+    return None;
+}
+
+pub fn run_differential(
+    day: u32,
+    seed: u64,
+    size: usize,
+    count: u64,
+) -> Option<(Vec<String>, Disagreement)> {
+    let inputs = examples::example_lines(day, 1)
+        .into_iter()
+        .chain((seed..(seed + count)).map(|case_seed| generate::generate(day, case_seed, size)));
+    for lines in inputs {
+        if let Some(disagreement) = compare(day, &lines) {
+            return Some((lines, disagreement));
+        }
+    }
+
+    return None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oracles_match_examples() {
+        let expected: [(u32, Solver, i64); 10] = [
+            (5, day_5_step_1, 35),
+            (5, day_5_step_2, 46),
+            (6, day_6_step_1, 288),
+            (6, day_6_step_2, 71503),
+            (11, |lines| day_11_sum_of_distances(lines, 2), 374),
+            (11, |lines| day_11_sum_of_distances(lines, 100), 8410),
+            (12, day_12_step_1, 21),
+            (12, day_12_step_2, 525152),
+            (13, |lines| day_13_total(lines, 0), 405),
+            (13, |lines| day_13_total(lines, 1), 400),
+        ];
+
+        for (day, oracle, answer) in expected {
+            assert_eq!(oracle(examples::example_lines(day, 1).unwrap()), answer);
+        }
+    }
+
+    #[test]
+    fn test_solvers_match_oracles() {
+        for (day, size) in [(5, 8), (6, 10), (11, 20), (12, 6), (13, 5)] {
+            let result = run_differential(day, 0, size, 20);
+            assert_eq!(result, None, "day {}", day);
+        }
+    }
+
+    #[test]
+    fn test_panics_are_reported() {
+        // arrange
+        let lines = vec!["Time: 7".to_owned()];
+
+        // act
+        let result = compare(6, &lines);
+
+        // assert
+        assert!(matches!(
+            result,
+            Some(Disagreement {
+                step: 1,
+                solver: Err(_),
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{day_10, day_11, day_14, day_16, day_17};
+use std::fs;
+use std::vec::Vec;
+
+pub type Color = (u8, u8, u8);
+
+pub const BACKGROUND: Color = (245, 245, 240);
+pub const FAINT: Color = (215, 215, 210);
+pub const WALL: Color = (60, 60, 70);
+pub const INSIDE: Color = (120, 200, 120);
+pub const HIGHLIGHT: Color = (240, 190, 40);
+pub const ACCENT: Color = (210, 70, 50);
+
+// one pixel per cell is too small to look at. svg images are scaled by the viewer instead
+const PPM_SCALE: usize = 6;
+const SVG_SCALE: usize = 10;
+
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    cells: Vec<Color>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Canvas {
+        return Canvas {
+            width,
+            height,
+            cells: vec![BACKGROUND; width * height],
+        };
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, color: Color) {
+        self.cells[row * self.width + column] = color;
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> Color {
+        return self.cells[row * self.width + column];
+    }
+
+    fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!(
+            "P6\n{} {}\n255\n",
+            self.width * PPM_SCALE,
+            self.height * PPM_SCALE
+        )
+        .into_bytes();
+        for row in 0..(self.height * PPM_SCALE) {
+            for column in 0..(self.width * PPM_SCALE) {
+                let (red, green, blue) = self.get(row / PPM_SCALE, column / PPM_SCALE);
+                bytes.extend([red, green, blue]);
+            }
+        }
+        return bytes;
+    }
+
+    fn to_svg(&self) -> Vec<u8> {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n",
+            self.width * SVG_SCALE,
+            self.height * SVG_SCALE,
+            self.width,
+            self.height
+        );
+        svg += &format!(
+            "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+            self.width,
+            self.height,
+            to_hex(BACKGROUND)
+        );
+        for row in 0..self.height {
+            // merge runs of the same color into one rect, to keep big grids manageable
+            let mut column = 0;
+            while column < self.width {
+                let color = self.get(row, column);
+                let mut end = column + 1;
+                while end < self.width && self.get(row, end) == color {
+                    end += 1;
+                }
+                if color != BACKGROUND {
+                    svg += &format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" fill=\"{}\"/>\n",
+                        column,
+                        row,
+                        end - column,
+                        to_hex(color)
+                    );
+                }
+                column = end;
+            }
+        }
+        svg += "</svg>\n";
+        return svg.into_bytes();
+    }
+
+    pub fn write(&self, path: &str) {
+        let bytes = if path.ends_with(".svg") {
+            self.to_svg()
+        } else if path.ends_with(".ppm") {
+            self.to_ppm()
+        } else {
+            panic!("can only render .svg or .ppm files");
+        };
+        fs::write(path, bytes).expect("could not write image");
+    }
+}
+
+fn to_hex((red, green, blue): Color) -> String {
+    return format!("#{:02x}{:02x}{:02x}", red, green, blue);
+}
+
+pub fn blend(from: Color, to: Color, amount: f64) -> Color {
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * amount).round() as u8;
+    return (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2));
+}
+
+// `num_cycles` defaults to the puzzle's
+pub fn render(day: u32, step: u32, lines: Vec<String>, num_cycles: Option<u64>) -> Canvas {
+    return match day {
+        10 => day_10::render(lines),
+        11 => day_11::render(lines, step),
+        14 => day_14::render(lines, step, num_cycles.unwrap_or(day_14::NUM_CYCLES)),
+        16 => day_16::render(lines, step),
+        17 => day_17::render(lines, step),
+        _ => panic!("day {} can't be rendered", day),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_lines(text: &str) -> Vec<String> {
+        return text.lines().map(|line| line.to_owned()).collect();
+    }
+
+    // draws the canvas as text, one character per cell, with ? for blended colors
+    fn draw(canvas: &Canvas) -> Vec<String> {
+        return (0..canvas.height)
+            .map(|row| {
+                (0..canvas.width)
+                    .map(|column| match canvas.get(row, column) {
+                        BACKGROUND => '.',
+                        FAINT => '-',
+                        WALL => '#',
+                        INSIDE => 'I',
+                        HIGHLIGHT => 'H',
+                        ACCENT => 'A',
+                        _ => '?',
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    #[test]
+    fn test_ppm_header_and_size() {
+        // arrange
+        let mut canvas = Canvas::new(2, 1);
+        canvas.set(0, 1, ACCENT);
+
+        // act
+        let result = canvas.to_ppm();
+
+        // assert
+        let header = b"P6\n12 6\n255\n";
+        assert!(result.starts_with(header));
+        assert_eq!(result.len(), header.len() + 12 * 6 * 3);
+        let get_pixel = |row: usize, column: usize| {
+            let index = header.len() + (row * 12 + column) * 3;
+            (result[index], result[index + 1], result[index + 2])
+        };
+        assert_eq!(
+            (get_pixel(0, 5), get_pixel(0, 6), get_pixel(5, 11)),
+            (BACKGROUND, ACCENT, ACCENT)
+        );
+    }
+
+    #[test]
+    fn test_svg_merges_runs() {
+        // arrange
+        let mut canvas = Canvas::new(3, 2);
+        canvas.set(0, 0, WALL);
+        canvas.set(0, 1, WALL);
+        canvas.set(1, 2, ACCENT);
+
+        // act
+        let result = String::from_utf8(canvas.to_svg()).unwrap();
+
+        // assert
+        assert_eq!(
+            result,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="20" viewBox="0 0 3 2" shape-rendering="crispEdges">
+<rect width="3" height="2" fill="#f5f5f0"/>
+<rect x="0" y="0" width="2" height="1" fill="#3c3c46"/>
+<rect x="2" y="1" width="1" height="1" fill="#d24632"/>
+</svg>
+"##
+        );
+    }
+
+    #[test]
+    fn test_render_day_10() {
+        // arrange
+        let lines = to_lines("S-7\n|.|\nL-J");
+
+        // act
+        let result = draw(&render(10, 2, lines, None));
+
+        // assert
+        assert_eq!(
+            result,
+            vec![
+                ".........",
+                ".#######.",
+                ".#.....#.",
+                ".#.III.#.",
+                ".#.III.#.",
+                ".#.III.#.",
+                ".#.....#.",
+                ".#######.",
+                ".........",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_day_11() {
+        // arrange
+        let lines = to_lines("#..\n...\n..#");
+
+        // act
+        let result = (
+            draw(&render(11, 1, lines.clone(), None)),
+            draw(&render(11, 2, lines, None)),
+        );
+
+        // assert
+        // empty lines are drawn wider, though step 2's are capped so the image stays small
+        assert_eq!(result.0, vec!["A--.", "----", "----", ".--A"]);
+        assert_eq!(
+            result.1,
+            vec!["A----.", "------", "------", "------", "------", ".----A"]
+        );
+    }
+
+    #[test]
+    fn test_render_day_16() {
+        // arrange
+        let lines = to_lines(".|.\n...\n.\\/\n-..");
+
+        // act
+        let result = draw(&render(16, 1, lines, None));
+
+        // assert
+        assert_eq!(result, vec!["HAH", ".HH", ".AA", "#.."]);
+    }
+
+    #[test]
+    fn test_render_day_17() {
+        // arrange
+        let lines = to_lines("111\n551\n551");
+
+        // act
+        let result = draw(&render(17, 1, lines, None));
+
+        // assert
+        // the path is drawn over the heat loss, which is shaded between the background and walls
+        assert_eq!(result, vec!["AAA", "??A", "??A"]);
+    }
+}
@@ -0,0 +1,468 @@
+use crate::{day_11, day_12, day_14, day_17, day_3, day_7};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
+
+const HELP: &str = "commands:
+  run <step>           run step 1 or 2 on the loaded input
+  set <name> <value>   override a parameter for this day
+  unset <name>         go back to the puzzle's value for a parameter
+  params               list this day's parameters
+  row <r>              print a line of the input
+  cell <r> <c>         print a single byte of the input
+  record <r>           (day 12) count the arrangements of one record
+  hand <r>             (day 7) show the type and rank of one hand
+  edit <r> <c> <char>  (day 3) change a cell and update the sums
+  help
+  quit
+rows and columns are counted from 0";
+
+enum Model {
+    Schematic(day_3::EditableSchematic),
+    Image(day_11::Image),
+    Dish(day_14::Dish),
+    HeatLossLayout(Vec<Vec<u64>>),
+    Lines,
+}
+
+// parameters that only affect step 2 have no step 1 value
+fn get_parameters(day: u32) -> Vec<(&'static str, Option<u64>, u64)> {
+    return match day {
+        11 => vec![("expansion", Some(2), 1000000)],
+        14 => vec![("cycles", None, 1000000000)],
+        17 => vec![("min-run", Some(1), 4), ("max-run", Some(3), 10)],
+        _ => Vec::new(),
+    };
+}
+
+struct Session {
+    day: u32,
+    lines: Vec<String>,
+    model: Model,
+    overrides: HashMap<String, u64>,
+}
+
+impl Session {
+    fn new(day: u32, lines: Vec<String>) -> Session {
+        let model = match day {
+            3 => Model::Schematic(day_3::EditableSchematic::new(
+                lines.clone(),
+                &day_3::Rules::default(),
+            )),
+            11 => Model::Image(day_11::Image::parse(lines.clone())),
+            14 => Model::Dish(day_14::parse_dish(lines.clone())),
+            17 => Model::HeatLossLayout(day_17::parse_layout(lines.clone())),
+            _ => Model::Lines,
+        };
+        return Session {
+            day,
+            lines,
+            model,
+            overrides: HashMap::new(),
+        };
+    }
+
+    fn get_parameter(&self, name: &str, step: u32) -> u64 {
+        if let Some(value) = self.overrides.get(name) {
+            return *value;
+        }
+        let (_, step_1_value, step_2_value) = get_parameters(self.day)
+            .into_iter()
+            .find(|(parameter_name, _, _)| *parameter_name == name)
+            .unwrap();
+        return if step == 1 {
+            step_1_value.unwrap()
+        } else {
+            step_2_value
+        };
+    }
+
+    fn run(&self, step: u32) {
+        let start = Instant::now();
+        // the panic message is already printed by the default hook, so only the session needs
+        // to survive it
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.run_unchecked(step)));
+        if result.is_err() {
+            println!("step {} panicked", step);
+        }
+        println!("took {:?}", start.elapsed());
+    }
+
+    fn run_unchecked(&self, step: u32) {
+        match &self.model {
+            Model::Schematic(schematic) if step == 1 => {
+                println!("sum: {}", schematic.get_part_number_sum())
+            }
+            Model::Schematic(schematic) => println!("sum: {}", schematic.get_gear_score_sum()),
+            Model::Image(image) => {
+                let expansion = self.get_parameter("expansion", step);
+                println!(
+                    "sum of distances: {}",
+                    image.sum_of_distances(expansion as usize - 1)
+                );
+            }
+            // step 1 only tilts north once, so cycles don't apply to it
+            Model::Dish(dish) if step == 2 => {
+                let cycles = self.get_parameter("cycles", step);
+                println!("{}", day_14::get_load_after_cycles(dish.clone(), cycles));
+            }
+            Model::HeatLossLayout(layout) => {
+                let min_run = self.get_parameter("min-run", step) as u8;
+                let max_run = self.get_parameter("max-run", step) as u8;
+                match day_17::find_minimum_heat_loss(layout, min_run, max_run) {
+                    Some((cost, _)) => println!("result: {}", cost),
+                    None => println!("did not get to end"),
+                }
+            }
+            _ => crate::solve(
+                &format!("day-{}-step-{}", self.day, step),
+                self.lines.clone().into_iter(),
+            ),
+        }
+    }
+
+    fn set(&mut self, name: &str, value: &str) {
+        if !get_parameters(self.day)
+            .iter()
+            .any(|(parameter_name, _, _)| *parameter_name == name)
+        {
+            println!("day {} has no parameter {}", self.day, name);
+            return;
+        }
+        let value = match value.parse::<u64>() {
+            Ok(value) => value,
+            Err(_) => {
+                println!("{} is not a number", value);
+                return;
+            }
+        };
+        if name == "expansion" && value == 0 {
+            println!("expansion has to be at least 1");
+            return;
+        }
+        if (name == "min-run" || name == "max-run") && value > u8::MAX as u64 {
+            println!("{} can be at most {}", name, u8::MAX);
+            return;
+        }
+        self.overrides.insert(name.to_owned(), value);
+    }
+
+    fn print_parameters(&self) {
+        let parameters = get_parameters(self.day);
+        if parameters.is_empty() {
+            println!("day {} has no parameters", self.day);
+        }
+        for (name, step_1_value, step_2_value) in parameters {
+            match (self.overrides.get(name), step_1_value) {
+                (Some(value), _) => println!("{} = {}", name, value),
+                (None, Some(step_1_value)) => println!(
+                    "{} = {} (step 1), {} (step 2)",
+                    name, step_1_value, step_2_value
+                ),
+                (None, None) => println!("{} = {} (step 2 only)", name, step_2_value),
+            }
+        }
+    }
+
+    fn get_line(&self, row: &str) -> Option<&String> {
+        let line = row
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| self.lines.get(index));
+        if line.is_none() {
+            println!("no row {}", row);
+        }
+        return line;
+    }
+
+    fn print_cell(&self, row: &str, column: &str) {
+        let Some(line) = self.get_line(row) else {
+            return;
+        };
+        match column
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| line.as_bytes().get(index))
+        {
+            Some(byte) => println!("{}", *byte as char),
+            None => println!("no column {}", column),
+        }
+    }
+
+    fn print_record(&self, row: &str) {
+        let Some(line) = self.get_line(row) else {
+            return;
+        };
+        if !is_record(line) {
+            println!("row {} is not a record followed by group sizes", row);
+            return;
+        }
+        println!("{}", line);
+        println!("arrangements: {}", day_12::count_for_line(line, false));
+        println!(
+            "unfolded arrangements: {}",
+            day_12::count_for_line(line, true)
+        );
+    }
+
+    fn edit(&mut self, row: &str, column: &str, value: &str) {
+        let Model::Schematic(schematic) = &mut self.model else {
+            return;
+        };
+        let (Ok(row), Ok(column)) = (row.parse::<usize>(), column.parse::<usize>()) else {
+            println!("rows and columns have to be numbers");
+            return;
+        };
+        if row >= self.lines.len() {
+            println!("no row {}", row);
+            return;
+        }
+        // editing past the end would pad the line up to the column
+        if column >= self.lines[row].len() {
+            println!("no column {}", column);
+            return;
+        }
+        if value.len() != 1 {
+            println!("can only set a cell to one ASCII character");
+            return;
+        }
+        if !self.lines[row].is_ascii() {
+            println!("can only edit ASCII lines");
+            return;
+        }
+        let num_rows = schematic.apply_edits(&[day_3::Edit {
+            row,
+            column,
+            byte: value.as_bytes()[0],
+        }]);
+        self.lines[row] = schematic.get_lines()[row].clone();
+        println!(
+            "looked at {} rows, part number sum {}, gear score sum {}",
+            num_rows,
+            schematic.get_part_number_sum(),
+            schematic.get_gear_score_sum()
+        );
+    }
+
+    fn print_hand(&self, row: &str) {
+        let Some(line) = self.get_line(row) else {
+            return;
+        };
+        let Some((hand, bid)) = parse_hand(line) else {
+            println!("row {} is not a hand of 5 cards followed by a bid", row);
+            return;
+        };
+        println!("{} (bid {})", hand, bid);
+        // rows that aren't hands don't get a rank, and don't push anything else down
+        let hands: Vec<&str> = self
+            .lines
+            .iter()
+            .filter_map(|other| parse_hand(other).map(|(other_hand, _)| other_hand))
+            .collect();
+        for (step, is_step_2) in [(1, false), (2, true)] {
+            let value = day_7::evaluate_hand_value(hand, is_step_2);
+            // ranks start at 1 for the weakest hand
+            let rank = 1 + hands
+                .iter()
+                .filter(|other_hand| day_7::evaluate_hand_value(other_hand, is_step_2) < value)
+                .count();
+            println!(
+                "step {}: {}, rank {} of {}",
+                step,
+                day_7::HAND_TYPE_NAMES[day_7::get_hand_type_value(hand, is_step_2) as usize],
+                rank,
+                hands.len()
+            );
+        }
+    }
+}
+
+fn parse_hand(line: &str) -> Option<(&str, &str)> {
+    let (hand, bid) = line.split_once(' ')?;
+    if hand.len() != 5
+        || !hand.bytes().all(|byte| b"23456789TJQKA".contains(&byte))
+        || bid.parse::<u64>().is_err()
+    {
+        return None;
+    }
+    return Some((hand, bid));
+}
+
+// Checks a day 12 line is a record of springs, then a space and comma-separated group sizes.
+// day_12 doesn't handle empty groups, so those are rejected too.
+fn is_record(line: &str) -> bool {
+    let Some((record, groups)) = line.split_once(' ') else {
+        return false;
+    };
+    return record.bytes().all(|byte| b".#?".contains(&byte))
+        && groups
+            .split(',')
+            .all(|group| group.parse::<usize>().is_ok_and(|size| size > 0));
+}
+
+pub fn repl(day: u32, lines: Vec<String>) {
+    let mut session = Session::new(day, lines);
+
+    println!(
+        "loaded day {} ({} lines), type help for commands",
+        day,
+        session.lines.len()
+    );
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+
+        let mut command = String::new();
+        if stdin.lock().read_line(&mut command).unwrap() == 0 {
+            break;
+        }
+
+        let words: Vec<&str> = command.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {}
+            ["run", "1"] => session.run(1),
+            ["run", "2"] => session.run(2),
+            ["set", name, value] => session.set(name, value),
+            ["unset", name] => {
+                session.overrides.remove(*name);
+            }
+            ["params"] => session.print_parameters(),
+            ["row", row] => {
+                if let Some(line) = session.get_line(row) {
+                    println!("{}", line);
+                }
+            }
+            ["cell", row, column] => session.print_cell(row, column),
+            ["record", row] if day == 12 => session.print_record(row),
+            ["hand", row] if day == 7 => session.print_hand(row),
+            ["edit", row, column, value] if day == 3 => session.edit(row, column, value),
+            ["help"] => println!("{}", HELP),
+            ["quit"] | ["exit"] => break,
+            _ => println!("unknown command, type help for commands"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_rejects_bad_values() {
+        // arrange
+        let mut session = Session::new(17, crate::examples::example_lines(17, 1).unwrap());
+
+        // act
+        session.set("expansion", "3");
+        session.set("min-run", "two");
+        session.set("max-run", "256");
+
+        // assert
+        assert!(session.overrides.is_empty());
+    }
+
+    #[test]
+    fn test_set_rejects_zero_expansion() {
+        // arrange
+        let mut session = Session::new(11, crate::examples::example_lines(11, 1).unwrap());
+
+        // act
+        session.set("expansion", "0");
+
+        // assert
+        assert!(session.overrides.is_empty());
+    }
+
+    #[test]
+    fn test_get_parameter() {
+        // arrange
+        let mut session = Session::new(17, crate::examples::example_lines(17, 1).unwrap());
+        let defaults = (
+            session.get_parameter("min-run", 1),
+            session.get_parameter("min-run", 2),
+        );
+
+        // act
+        session.set("min-run", "2");
+        let overridden = (
+            session.get_parameter("min-run", 1),
+            session.get_parameter("min-run", 2),
+        );
+
+        // assert
+        assert_eq!((defaults, overridden), ((1, 4), (2, 2)));
+    }
+
+    #[test]
+    fn test_parse_hand() {
+        // arrange
+        let lines = ["32T3K 765", "32T3 765", "32T3X 765", "32T3K -1", "32T3K"];
+
+        // act
+        let result: Vec<Option<(&str, &str)>> = lines.iter().map(|line| parse_hand(line)).collect();
+
+        // assert
+        assert_eq!(result, vec![Some(("32T3K", "765")), None, None, None, None]);
+    }
+
+    #[test]
+    fn test_is_record() {
+        // arrange
+        let lines = [
+            "???.### 1,1,3",
+            "???.###",
+            "???.### 1,,3",
+            "??x.### 1,1,3",
+            "???.### 0,3",
+        ];
+
+        // act
+        let result: Vec<bool> = lines.iter().map(|line| is_record(line)).collect();
+
+        // assert
+        assert_eq!(result, vec![true, false, false, false, false]);
+    }
+
+    #[test]
+    fn test_edit_updates_sums() {
+        // arrange
+        let mut session = Session::new(3, crate::examples::example_lines(3, 1).unwrap());
+
+        // act
+        session.edit("0", "0", ".");
+
+        // assert
+        let Model::Schematic(schematic) = &session.model else {
+            panic!("day 3 should have a schematic");
+        };
+        assert_eq!(session.lines[0], ".67..114..");
+        assert_eq!(
+            (
+                schematic.get_part_number_sum(),
+                schematic.get_gear_score_sum()
+            ),
+            (4361 - 467 + 67, 467835 - 467 * 35 + 67 * 35)
+        );
+    }
+
+    #[test]
+    fn test_edit_rejects_columns_past_the_end() {
+        // arrange
+        let lines = crate::examples::example_lines(3, 1).unwrap();
+        let mut session = Session::new(3, lines.clone());
+
+        // act
+        session.edit("0", "10", "*");
+        session.edit("0", "4000000000", "*");
+
+        // assert
+        assert_eq!(session.lines, lines);
+        let Model::Schematic(schematic) = &session.model else {
+            panic!("day 3 should have a schematic");
+        };
+        assert_eq!(schematic.get_lines(), lines.as_slice());
+    }
+}
@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+fn get_modified_time(path: &Path) -> Option<SystemTime> {
+    return fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+}
+
+// re-runs through cargo so that source edits get rebuilt
+pub fn watch(day: u32, step: Option<u32>, input_path: &str, run_tests: bool) {
+    let project_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let source_path = project_dir.join("src").join(format!("day_{}.rs", day));
+    // cargo runs from the project directory, so the input path has to be absolute
+    let input_path: PathBuf = fs::canonicalize(input_path).expect("could not find input");
+
+    let mut command = Command::new("cargo");
+    command.current_dir(project_dir);
+    if run_tests {
+        command.args(["test", "--quiet", &format!("day_{}::", day)]);
+    } else {
+        command.args(["run", "--quiet", "--release", "--", "run"]);
+        command.arg(&input_path);
+        command.args(["--day", &day.to_string()]);
+        if let Some(step) = step {
+            command.args(["--step", &step.to_string()]);
+        }
+    }
+
+    let mut last_modified_times: Option<(Option<SystemTime>, Option<SystemTime>)> = None;
+    loop {
+        let modified_times = (
+            get_modified_time(&source_path),
+            get_modified_time(&input_path),
+        );
+        if last_modified_times == Some(modified_times) {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+        last_modified_times = Some(modified_times);
+
+        // clear the screen and move the cursor back to the top
+        print!("\x1b[2J\x1b[H");
+        println!(
+            "watching {} and {}",
+            source_path.display(),
+            input_path.display()
+        );
+        println!();
+
+        let start = Instant::now();
+        let status = command.status().expect("could not run cargo");
+        println!();
+        println!("{} after {:?}", status, start.elapsed());
+    }
+}